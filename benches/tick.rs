@@ -0,0 +1,43 @@
+//! Compares `tick` and `tick_simd` on a 256x256 grid.
+//!
+//! Natively both run the scalar path; build for wasm with
+//! `-C target-feature=+simd128` to measure the SIMD kernel.
+
+use std::time::Instant;
+
+use wasm_game_of_life::Universe;
+
+const SIZE: u32 = 256;
+const TICKS: u32 = 200;
+
+fn soup() -> Universe {
+    let mut universe = Universe::empty(SIZE, SIZE);
+    let mut state = 0x2545_f491_u32;
+    let cells: Vec<(u32, u32)> = (0..SIZE * SIZE)
+        .filter(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state & 1 == 1
+        })
+        .map(|idx| (idx / SIZE, idx % SIZE))
+        .collect();
+    universe.set_cells(&cells);
+    universe
+}
+
+fn bench(name: &str, tick: fn(&mut Universe)) {
+    let mut universe = soup();
+    let start = Instant::now();
+    for _ in 0..TICKS {
+        tick(&mut universe);
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    let cells_per_second = (SIZE * SIZE * TICKS) as f64 / elapsed;
+    println!("{:>10}: {:.1} Mcells/s", name, cells_per_second / 1e6);
+}
+
+fn main() {
+    bench("tick", Universe::tick);
+    bench("tick_simd", Universe::tick_simd);
+}
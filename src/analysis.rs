@@ -0,0 +1,375 @@
+//! Read-only measurements of the current board.
+
+use std::collections::HashSet;
+use std::f64::consts::TAU;
+use wasm_bindgen::prelude::*;
+
+use crate::{lz77, Cell, Error, Universe};
+
+/// Which reflections and rotations leave the live cells unchanged, as
+/// reported by `Universe::detect_symmetry`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    None,
+    /// Unchanged when mirrored left to right.
+    Horizontal,
+    /// Unchanged when mirrored top to bottom.
+    Vertical,
+    /// Unchanged by both mirrors, and so by a half turn as well.
+    Both,
+    /// Unchanged by a half turn, but by neither mirror.
+    Rotational,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Spatial autocorrelation of the board at toroidal offset
+    /// (`lag_row`, `lag_col`). Cells are read as +1 (alive) and -1 (dead)
+    /// and the products of each cell with its offset partner are averaged,
+    /// so lag (0, 0) is always 1.0, a pattern that repeats at the offset
+    /// scores near 1.0 and one that inverts, like a checkerboard shifted by
+    /// one column, scores near -1.0.
+    pub fn measure_autocorrelation(&self, lag_row: i32, lag_col: i32) -> f64 {
+        if self.cells.is_empty() {
+            return 0.0;
+        }
+
+        let spin = |cell: Cell| if cell == Cell::Alive { 1.0 } else { -1.0 };
+        let mut sum = 0.0;
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let here = self.cells[self.get_index(row, col)];
+                let there = self.cells
+                    [self.wrapped_index(row as i64 + lag_row as i64, col as i64 + lag_col as i64)];
+                sum += spin(here) * spin(there);
+            }
+        }
+        sum / self.cells.len() as f64
+    }
+
+    /// Live cells in the `height` x `width` rectangle at (`row`, `col`),
+    /// clamped to the board rather than wrapping.
+    pub fn count_alive_in_rect(&self, row: u32, col: u32, height: u32, width: u32) -> u32 {
+        let row = row.min(self.height);
+        let col = col.min(self.width);
+        let height = height.min(self.height - row);
+        let width = width.min(self.width - col);
+        (row..row + height)
+            .map(|r| {
+                let start = self.get_index(r, col);
+                self.cells[start..start + width as usize]
+                    .iter()
+                    .filter(|&&cell| cell == Cell::Alive)
+                    .count() as u32
+            })
+            .sum()
+    }
+
+    /// The fraction of live cells in the `2 * radius + 1` square centred on
+    /// (`row`, `col`), wrapping around the edges. A square larger than the
+    /// board wraps onto itself and counts some cells more than once.
+    pub fn local_density(&self, row: u32, col: u32, radius: u32) -> f64 {
+        if self.cells.is_empty() {
+            return 0.0;
+        }
+
+        let radius = radius as i64;
+        let mut alive = 0u64;
+        for delta_row in -radius..=radius {
+            for delta_col in -radius..=radius {
+                let idx = self.wrapped_index(row as i64 + delta_row, col as i64 + delta_col);
+                alive += self.cells[idx] as u64;
+            }
+        }
+        let side = (2 * radius + 1) as f64;
+        alive as f64 / (side * side)
+    }
+
+    /// The board shrunk to `target_w` x `target_h` for a minimap, row by
+    /// row. Each output pixel is the number of live cells in its block of
+    /// the board, capped at 255. Blocks split the board as evenly as
+    /// possible, so when the sizes don't divide some are a cell larger.
+    /// Fails if the minimap would have more than `MAX_CELLS` pixels.
+    pub fn minimap(&self, target_w: u32, target_h: u32) -> Result<Vec<u8>, Error> {
+        let edges = |size: u32, parts: u32, i: u32| {
+            let edge = |i: u32| (i as u64 * size as u64 / parts as u64) as u32;
+            (edge(i), edge(i + 1) - edge(i))
+        };
+        let mut pixels = Vec::with_capacity(crate::checked_cell_count(target_w, target_h)?);
+        for i in 0..target_h {
+            let (row, height) = edges(self.height, target_h, i);
+            for j in 0..target_w {
+                let (col, width) = edges(self.width, target_w, j);
+                let alive = self.count_alive_in_rect(row, col, height, width);
+                pixels.push(alive.min(255) as u8);
+            }
+        }
+        Ok(pixels)
+    }
+
+    /// `local_density` at every cell, row by row.
+    pub fn density_map(&self, radius: u32) -> Box<[f32]> {
+        if self.cells.is_empty() {
+            return Box::new([]);
+        }
+
+        // Sum each row's horizontal windows, then those sums vertically.
+        let radius = radius as i64;
+        let mut across = vec![0u32; self.cells.len()];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                across[self.get_index(row, col)] = (-radius..=radius)
+                    .map(|delta| {
+                        self.cells[self.wrapped_index(row as i64, col as i64 + delta)] as u32
+                    })
+                    .sum();
+            }
+        }
+
+        let side = (2 * radius + 1) as f32;
+        let mut densities = vec![0.0; self.cells.len()];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let alive: u32 = (-radius..=radius)
+                    .map(|delta| across[self.wrapped_index(row as i64 + delta, col as i64)])
+                    .sum();
+                densities[self.get_index(row, col)] = alive as f32 / (side * side);
+            }
+        }
+        densities.into_boxed_slice()
+    }
+
+    /// Runs `ticks` generations on two copies of the board, one wrapping
+    /// around as usual and one treating everything beyond the edges as
+    /// permanently dead, and returns how many cells differ at the end.
+    /// Both copies tick every cell, ignoring any active region.
+    pub fn count_difference_between_boundary_modes(&self, ticks: u32) -> u32 {
+        if self.cells.is_empty() {
+            return 0;
+        }
+        let mut toroidal = self.scratch();
+        toroidal.active_region = None;
+        // A ring of dead cells around the board, cleared again after every
+        // step, is exactly a dead boundary.
+        let mut bounded = self.scratch();
+        bounded
+            .pad(1)
+            .expect("a board in memory leaves room for a one-cell margin");
+        for _ in 0..ticks {
+            toroidal.step();
+            bounded.step();
+            bounded.clear_border(1);
+        }
+
+        let mut differences = 0;
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let wrapped = toroidal.cells[toroidal.get_index(row, col)];
+                let walled = bounded.cells[bounded.get_index(row + 1, col + 1)];
+                if wrapped != walled {
+                    differences += 1;
+                }
+            }
+        }
+        differences
+    }
+
+    /// A rough proxy for the board's Kolmogorov complexity: the size in bytes
+    /// of `cells_packed` after LZ77 compression. Ordered boards compress to
+    /// a few bytes, while random ones stay close to the packed size of
+    /// `ceil(width * height / 8)` bytes, or slightly above it.
+    pub fn kolmogorov_estimate(&self) -> u32 {
+        lz77::compress(&self.cells_packed()).len() as u32
+    }
+
+    /// Magnitudes of the 2D discrete Fourier transform of the board,
+    /// sampled at `resolution` x `resolution` evenly spaced cells with live
+    /// cells as 1 and dead as 0. The mean is subtracted first, so the
+    /// constant term is always 0 and only structure shows up. Entry
+    /// `u * resolution + v` is the magnitude at `u` cycles down and `v`
+    /// across the samples, divided by the number of samples. A checkerboard
+    /// sampled cell for cell, with `resolution` equal to an even board
+    /// size, peaks at the Nyquist frequency `u = v = resolution / 2`.
+    ///
+    /// Samples are single cells, so detail finer than their spacing
+    /// aliases, as it would for any undersampled signal. The transform is
+    /// computed directly, row by row and then column by column, so keep
+    /// `resolution` modest: the cost grows as its cube.
+    pub fn spatial_power_spectrum(&self, resolution: u32) -> Box<[f32]> {
+        let n = resolution as usize;
+        if n == 0 || self.cells.is_empty() {
+            return vec![0.0; n * n].into_boxed_slice();
+        }
+
+        let mut samples: Vec<f64> = (0..n * n)
+            .map(|i| {
+                let row = (i / n) as u64 * self.height as u64 / n as u64;
+                let col = (i % n) as u64 * self.width as u64 / n as u64;
+                self.cells[self.get_index(row as u32, col as u32)] as u8 as f64
+            })
+            .collect();
+        let mean = samples.iter().sum::<f64>() / (n * n) as f64;
+        samples.iter_mut().for_each(|sample| *sample -= mean);
+
+        let twiddles: Vec<(f64, f64)> = (0..n)
+            .map(|k| {
+                let angle = -TAU * k as f64 / n as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        // (re, im) of the transform along rows, then along columns.
+        let mut rows = vec![(0.0, 0.0); n * n];
+        for row in 0..n {
+            for v in 0..n {
+                rows[row * n + v] = (0..n).fold((0.0, 0.0), |(re, im), col| {
+                    let (cos, sin) = twiddles[v * col % n];
+                    let x = samples[row * n + col];
+                    (re + x * cos, im + x * sin)
+                });
+            }
+        }
+        let mut spectrum = vec![0.0; n * n];
+        for u in 0..n {
+            for v in 0..n {
+                let (re, im) = (0..n).fold((0.0, 0.0), |(re, im), row| {
+                    let (cos, sin) = twiddles[u * row % n];
+                    let (x_re, x_im) = rows[row * n + v];
+                    (re + x_re * cos - x_im * sin, im + x_re * sin + x_im * cos)
+                });
+                spectrum[u * n + v] = (re.hypot(im) / (n * n) as f64) as f32;
+            }
+        }
+        spectrum.into_boxed_slice()
+    }
+
+    /// The Hamming distance to `target`: how many cells differ between the
+    /// two boards. Both must have the same dimensions.
+    pub fn distance_to(&self, target: &Universe) -> Result<u32, Error> {
+        if (self.width, self.height) != (target.width, target.height) {
+            return Err(Error::new(format!(
+                "cannot compare a {}x{} universe with a {}x{} one",
+                self.width, self.height, target.width, target.height
+            )));
+        }
+        Ok(self
+            .cells
+            .iter()
+            .zip(&target.cells)
+            .filter(|(here, there)| here != there)
+            .count() as u32)
+    }
+
+    /// The symmetry of the live cells within their bounding box, so where
+    /// the pattern sits on the board doesn't matter. Wrapping is ignored, as
+    /// in `classify`. An empty board counts as fully symmetric.
+    pub fn detect_symmetry(&self) -> Symmetry {
+        let shape = self.shape();
+        let height = shape.iter().map(|&(row, _)| row + 1).max().unwrap_or(0);
+        let width = shape.iter().map(|&(_, col)| col + 1).max().unwrap_or(0);
+        let cells: HashSet<(u32, u32)> = shape.iter().copied().collect();
+        let unchanged_by = |transform: &dyn Fn(u32, u32) -> (u32, u32)| {
+            shape
+                .iter()
+                .all(|&(row, col)| cells.contains(&transform(row, col)))
+        };
+
+        let horizontal = unchanged_by(&|row, col| (row, width - 1 - col));
+        let vertical = unchanged_by(&|row, col| (height - 1 - row, col));
+        match (horizontal, vertical) {
+            (true, true) => Symmetry::Both,
+            (true, false) => Symmetry::Horizontal,
+            (false, true) => Symmetry::Vertical,
+            (false, false) if unchanged_by(&|row, col| (height - 1 - row, width - 1 - col)) => {
+                Symmetry::Rotational
+            }
+            (false, false) => Symmetry::None,
+        }
+    }
+}
+
+impl Universe {
+    /// Which of the eight neighbours of (`row`, `col`) are alive, wrapping
+    /// around the edges, in reading order: NW, N, NE, W, E, SW, S, SE. The
+    /// number of `true`s is what the rule sees.
+    pub fn neighbor_breakdown(&self, row: u32, col: u32) -> [bool; 8] {
+        let mut alive = [false; 8];
+        let neighbors = (-1..=1)
+            .flat_map(|delta_row| (-1..=1).map(move |delta_col| (delta_row, delta_col)))
+            .filter(|&offset| offset != (0, 0));
+        for (slot, (delta_row, delta_col)) in alive.iter_mut().zip(neighbors) {
+            let idx = self.wrapped_index(row as i64 + delta_row, col as i64 + delta_col);
+            *slot = self.cells[idx] == Cell::Alive;
+        }
+        alive
+    }
+
+    /// How many cells, alive or dead, currently have 0 to 8 live
+    /// neighbours, counted the way `tick` counts them. A board whose cells
+    /// mostly sit at counts the rule leaves alone is close to stable.
+    pub fn neighbor_count_histogram(&self) -> [u32; 9] {
+        let mut histogram = [0; 9];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                histogram[self.live_neighbor_count(row, col) as usize] += 1;
+            }
+        }
+        histogram
+    }
+
+    /// The average (row, col) of the live cells, or `None` on an empty board.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        let (mut rows, mut cols, mut count) = (0.0, 0.0, 0.0);
+        for (row, col) in self.live_positions() {
+            rows += row as f64;
+            cols += col as f64;
+            count += 1.0;
+        }
+        if count == 0.0 {
+            None
+        } else {
+            Some((rows / count, cols / count))
+        }
+    }
+
+    /// Like `centroid`, but treats each axis as a circle so a pattern
+    /// straddling the wrap boundary is centred on the boundary rather than
+    /// averaged to the middle of the board. Coordinates are in
+    /// `[0, height)` x `[0, width)`. An axis whose cells are spread evenly
+    /// all around has no circular mean and falls back to the plain average.
+    pub fn toroidal_centroid(&self) -> Option<(f64, f64)> {
+        let (plain_row, plain_col) = self.centroid()?;
+        let (height, width) = (self.height as f64, self.width as f64);
+
+        let (mut row_sin, mut row_cos, mut col_sin, mut col_cos) = (0.0, 0.0, 0.0, 0.0);
+        for (row, col) in self.live_positions() {
+            let row_angle = TAU * row as f64 / height;
+            let col_angle = TAU * col as f64 / width;
+            row_sin += row_angle.sin();
+            row_cos += row_angle.cos();
+            col_sin += col_angle.sin();
+            col_cos += col_angle.cos();
+        }
+
+        let circular_mean = |sin: f64, cos: f64, size: f64, plain: f64| {
+            if sin.hypot(cos) < 1e-9 {
+                plain
+            } else {
+                (sin.atan2(cos) / TAU * size).rem_euclid(size)
+            }
+        };
+        Some((
+            circular_mean(row_sin, row_cos, height, plain_row),
+            circular_mean(col_sin, col_cos, width, plain_col),
+        ))
+    }
+
+    fn live_positions(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, &cell)| cell == Cell::Alive)
+            .map(move |(idx, _)| (idx as u32 / width, idx as u32 % width))
+    }
+}
@@ -0,0 +1,123 @@
+//! Setting a universe up in one go rather than constructing it and then
+//! mutating it. Every setting is checked in `build`, so a bad combination
+//! fails there instead of halfway through setup.
+
+use wasm_bindgen::prelude::*;
+
+use crate::config::BOUNDARY;
+use crate::rules::Rule;
+use crate::{utils, Error, Universe};
+
+/// Collects the settings for a universe. Anything left unset gets the
+/// same value `Universe::new` would give it, except the board, which
+/// starts empty unless a seed is given.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct UniverseBuilder {
+    width: u32,
+    height: u32,
+    rule: String,
+    boundary: String,
+    seed: Option<u64>,
+    patterns: Vec<Placement>,
+}
+
+#[derive(Clone, Debug)]
+struct Placement {
+    row: i32,
+    col: i32,
+    offsets: Vec<(i32, i32)>,
+}
+
+#[wasm_bindgen]
+impl UniverseBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> UniverseBuilder {
+        UniverseBuilder {
+            width: 64,
+            height: 64,
+            rule: Rule::default().to_string(),
+            boundary: BOUNDARY.to_string(),
+            seed: None,
+            patterns: Vec::new(),
+        }
+    }
+
+    pub fn width(mut self, width: u32) -> UniverseBuilder {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> UniverseBuilder {
+        self.height = height;
+        self
+    }
+
+    /// A life-like rule in any form `set_rule` accepts.
+    pub fn rule(mut self, rule: &str) -> UniverseBuilder {
+        self.rule = rule.to_string();
+        self
+    }
+
+    /// Only `"toroidal"` is supported for now.
+    pub fn boundary(mut self, boundary: &str) -> UniverseBuilder {
+        self.boundary = boundary.to_string();
+        self
+    }
+
+    /// Fills the board as `reset_with_seed` does, before any patterns are
+    /// placed.
+    pub fn seed(mut self, seed: u64) -> UniverseBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Validates the settings and creates the universe.
+    pub fn build(&self) -> Result<Universe, Error> {
+        utils::set_panic_hook();
+
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::new(format!(
+                "a universe must be at least 1x1, not {}x{}",
+                self.width, self.height
+            )));
+        }
+        crate::checked_cell_count(self.width, self.height)?;
+        let rule = Rule::parse(&self.rule)?;
+        if self.boundary != BOUNDARY {
+            return Err(Error::new(format!(
+                "unsupported boundary {:?}, only {:?} is available",
+                self.boundary, BOUNDARY
+            )));
+        }
+
+        let mut universe = Universe::empty(self.width, self.height);
+        universe.rule = rule;
+        if let Some(seed) = self.seed {
+            universe.reset_with_seed(seed);
+        }
+        for placement in &self.patterns {
+            universe.add_pattern(placement.row, placement.col, &placement.offsets);
+        }
+        Ok(universe)
+    }
+}
+
+impl UniverseBuilder {
+    /// Places `offsets` relative to (`row`, `col`), as `add_pattern` does.
+    /// Patterns are placed in the order given, after seeding.
+    pub fn pattern(mut self, row: i32, col: i32, offsets: &[(i32, i32)]) -> UniverseBuilder {
+        self.patterns.push(Placement {
+            row,
+            col,
+            offsets: offsets.to_vec(),
+        });
+        self
+    }
+}
+
+impl Default for UniverseBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,186 @@
+//! Counting the objects on the board by kind, in the spirit of apgsearch.
+//!
+//! Live cells are grouped into objects: two cells belong to the same
+//! object when they are within two cells of each other (in both rows and
+//! columns, wrapping), close enough to share a neighbour and so influence
+//! each other. Each object is then classified on its own with
+//! `classify_with_period`.
+
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, VecDeque};
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, PatternKind, Universe};
+
+// The longest period looked for. Objects that take longer to repeat are
+// counted as unknown.
+const CENSUS_MAX_PERIOD: u32 = 30;
+
+/// What `Universe::run_census` found.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CensusReport {
+    population: u32,
+    still_lifes: u32,
+    // Oscillator count by period.
+    oscillators: BTreeMap<u32, u32>,
+    spaceships: u32,
+    unknown: u32,
+}
+
+#[wasm_bindgen]
+impl CensusReport {
+    /// Live cells on the board.
+    #[wasm_bindgen(getter)]
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn still_lifes(&self) -> u32 {
+        self.still_lifes
+    }
+
+    /// Oscillators of every period.
+    #[wasm_bindgen(getter)]
+    pub fn oscillators(&self) -> u32 {
+        self.oscillators.values().sum()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn spaceships(&self) -> u32 {
+        self.spaceships
+    }
+
+    /// Objects that didn't repeat within 30 generations.
+    #[wasm_bindgen(getter)]
+    pub fn unknown(&self) -> u32 {
+        self.unknown
+    }
+
+    /// The periods of the oscillators found, smallest first.
+    #[wasm_bindgen(getter)]
+    pub fn oscillator_periods(&self) -> Box<[u32]> {
+        self.oscillators.keys().copied().collect()
+    }
+
+    /// Oscillators with exactly this `period`.
+    pub fn oscillators_with_period(&self, period: u32) -> u32 {
+        self.oscillators.get(&period).copied().unwrap_or(0)
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Splits the board into objects and classifies each; see the module
+    /// docs. Cost grows with the number of objects, each being run for up to
+    /// 30 generations.
+    pub fn run_census(&self) -> CensusReport {
+        let mut report = CensusReport {
+            population: self.population(),
+            ..CensusReport::default()
+        };
+
+        for object in self.objects() {
+            let mut alone = Universe::empty(self.width, self.height);
+            alone.rule = self.rule;
+            for idx in object {
+                alone.cells[idx] = Cell::Alive;
+            }
+            match alone.classify_with_period(CENSUS_MAX_PERIOD) {
+                (PatternKind::StillLife, _) => report.still_lifes += 1,
+                (PatternKind::Oscillator, Some(period)) => {
+                    *report.oscillators.entry(period).or_default() += 1
+                }
+                (PatternKind::Spaceship, _) => report.spaceships += 1,
+                _ => report.unknown += 1,
+            }
+        }
+        report
+    }
+}
+
+impl Universe {
+    /// Runs the board as a soup until it settles, then counts the objects
+    /// in the ash the way apgsearch does. The board settles once it repeats
+    /// a state from the last 30 generations, so oscillators count as
+    /// settled; after `max_steps` ticks whatever is left is counted anyway.
+    /// Objects are grouped as in `run_census` and keyed by a fingerprint of
+    /// their shape that is the same for every phase, rotation and reflection
+    /// of them. Returns `(fingerprint, count)` pairs, smallest fingerprint
+    /// first.
+    pub fn ash_census(&mut self, max_steps: u32) -> Vec<(u64, u32)> {
+        let mut recent: VecDeque<(u32, Vec<Cell>)> = VecDeque::new();
+        for _ in 0..max_steps {
+            let population = self.population();
+            let repeated = recent
+                .iter()
+                .any(|(seen, cells)| *seen == population && *cells == self.cells);
+            if repeated {
+                break;
+            }
+            if recent.len() == CENSUS_MAX_PERIOD as usize {
+                recent.pop_front();
+            }
+            recent.push_back((population, self.cells.clone()));
+            self.tick();
+        }
+
+        let mut census = BTreeMap::new();
+        for object in self.objects() {
+            let mut alone = Universe::empty(self.width, self.height);
+            alone.rule = self.rule;
+            for idx in object {
+                alone.cells[idx] = Cell::Alive;
+            }
+            *census
+                .entry(fingerprint(&alone.canonical_form(CENSUS_MAX_PERIOD)))
+                .or_default() += 1;
+        }
+        census.into_iter().collect()
+    }
+
+    // The flat indices of the live cells in each object.
+    fn objects(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.cells.len()];
+        let mut objects = Vec::new();
+
+        for start in 0..self.cells.len() {
+            if seen[start] || self.cells[start] == Cell::Dead {
+                continue;
+            }
+            seen[start] = true;
+            let mut object = vec![start];
+            let mut next = 0;
+            while let Some(&idx) = object.get(next) {
+                next += 1;
+                let (row, col) = (idx as u32 / self.width, idx as u32 % self.width);
+                for delta_row in -2..=2 {
+                    for delta_col in -2..=2 {
+                        let neighbor =
+                            self.wrapped_index(row as i64 + delta_row, col as i64 + delta_col);
+                        if !seen[neighbor] && self.cells[neighbor] == Cell::Alive {
+                            seen[neighbor] = true;
+                            object.push(neighbor);
+                        }
+                    }
+                }
+            }
+            objects.push(object);
+        }
+        objects
+    }
+}
+
+// The first eight bytes of the shape's SHA-256, so fingerprints stay the
+// same across platforms and releases.
+fn fingerprint(shape: &[(u32, u32)]) -> u64 {
+    let mut hasher = Sha256::new();
+    for &(row, col) in shape {
+        hasher.update(row.to_le_bytes());
+        hasher.update(col.to_le_bytes());
+    }
+    hasher.finalize()[..8]
+        .iter()
+        .fold(0, |bits, &byte| bits << 8 | byte as u64)
+}
@@ -0,0 +1,240 @@
+//! Period detection and classification of the board's contents.
+//!
+//! `detect_period` answers the literal question "when does this board
+//! repeat?", which on a torus includes spaceships that travel all the way
+//! around and come back. `classify` instead asks what the pattern is in
+//! isolation: it copies the live cells into a fresh universe with enough
+//! dead margin that nothing can reach an edge within `max_period` ticks,
+//! then looks for the pattern reappearing either in place (an oscillator)
+//! or shifted (a spaceship). The trade-off is cost, since the copy grows
+//! with `max_period`, and that interactions between a pattern and its own
+//! wrapped-around image on a small board are deliberately ignored.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{search, Cell, Error, Universe};
+
+/// The longest period `classify` looks for; larger values of `max_period`
+/// are treated as this. The isolated copy grows by two cells per tick of
+/// the search on each axis, and every tick steps the whole copy.
+pub const MAX_CLASSIFY_PERIOD: u32 = 256;
+
+// The largest board `state_space` will walk. Fingerprints are bitmasks, and
+// a 5x5 board already has 2^25 possible states.
+const STATE_SPACE_MAX_CELLS: u32 = 25;
+
+/// What `Universe::classify` found on the board.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternKind {
+    Empty,
+    StillLife,
+    Oscillator,
+    Spaceship,
+    /// Didn't repeat, even up to translation, within the search window.
+    Unknown,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The smallest number of ticks, at most `max_period`, after which the
+    /// board is exactly as it is now. Works on a copy.
+    pub fn detect_period(&self, max_period: u32) -> Option<u32> {
+        let mut copy = self.scratch();
+        (1..=max_period).find(|_| {
+            copy.step();
+            copy.cells == self.cells
+        })
+    }
+
+    /// Classifies the live cells as a single pattern, looking for repeats
+    /// within `max_period` ticks, at most `MAX_CLASSIFY_PERIOD`. Wrapping is
+    /// ignored; see the module docs.
+    pub fn classify(&self, max_period: u32) -> PatternKind {
+        self.classify_with_period(max_period).0
+    }
+
+    /// Ticks `2 * max_period` times and returns the smallest period, at most
+    /// `max_period`, with which the population repeated throughout. Cheaper
+    /// than `detect_period` and blind to translation, but also to anything
+    /// else that keeps the count the same: a blinker's is constant, so its
+    /// population period is 1.
+    pub fn population_period(&mut self, max_period: u32) -> Option<u32> {
+        let mut populations = vec![self.population()];
+        for _ in 0..2 * max_period {
+            self.tick();
+            populations.push(self.population());
+        }
+
+        (1..=max_period as usize)
+            .find(|&period| {
+                populations
+                    .iter()
+                    .zip(&populations[period..])
+                    .all(|(a, b)| a == b)
+            })
+            .map(|period| period as u32)
+    }
+
+    /// Ticks a copy of the board until it returns to a state it has already
+    /// been in, and returns the fingerprint of every state along the way:
+    /// the transient followed by one trip around the cycle, ending with the
+    /// first repeated state. A fingerprint has bit `i` set when the cell at
+    /// flat index `i` is alive. Only boards of at most 25 cells are allowed.
+    pub fn state_space(&self) -> Result<Vec<u64>, Error> {
+        if self.width * self.height > STATE_SPACE_MAX_CELLS {
+            return Err(Error::new(format!(
+                "state_space needs at most {} cells, not {}x{}",
+                STATE_SPACE_MAX_CELLS, self.width, self.height
+            )));
+        }
+
+        let mut copy = self.scratch();
+        let mut path = vec![copy.fingerprint()];
+        loop {
+            copy.step();
+            let fingerprint = copy.fingerprint();
+            let seen = path.contains(&fingerprint);
+            path.push(fingerprint);
+            if seen {
+                return Ok(path);
+            }
+        }
+    }
+}
+
+impl Universe {
+    /// Like `classify`, also returning the period when one was found.
+    pub fn classify_with_period(&self, max_period: u32) -> (PatternKind, Option<u32>) {
+        let max_period = max_period.min(MAX_CLASSIFY_PERIOD);
+        let mut isolated = match self.isolate(max_period + 1) {
+            Some(isolated) => isolated,
+            None => return (PatternKind::Empty, None),
+        };
+        let start = isolated.cells.clone();
+        let start_shape = isolated.shape();
+
+        for period in 1..=max_period {
+            isolated.step();
+            if isolated.cells == start {
+                let kind = if period == 1 {
+                    PatternKind::StillLife
+                } else {
+                    PatternKind::Oscillator
+                };
+                return (kind, Some(period));
+            }
+            if isolated.shape() == start_shape {
+                return (PatternKind::Spaceship, Some(period));
+            }
+        }
+        (PatternKind::Unknown, None)
+    }
+
+    fn fingerprint(&self) -> u64 {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, &cell)| cell == Cell::Alive)
+            .fold(0, |bits, (idx, _)| bits | 1 << idx)
+    }
+
+    // The live cells copied into an otherwise empty universe with `margin`
+    // dead cells on every side, or `None` if nothing is alive.
+    fn isolate(&self, margin: u32) -> Option<Universe> {
+        let (row_start, rows) = toroidal_extent(self.height, |row| {
+            (0..self.width).any(|col| self.cells[self.get_index(row, col)] == Cell::Alive)
+        })?;
+        let (col_start, cols) = toroidal_extent(self.width, |col| {
+            (0..self.height).any(|row| self.cells[self.get_index(row, col)] == Cell::Alive)
+        })?;
+
+        let mut isolated = Universe::empty(cols + 2 * margin, rows + 2 * margin);
+        isolated.rule = self.rule;
+        for row in 0..rows {
+            for col in 0..cols {
+                let source = self.get_index(
+                    (row_start + row) % self.height,
+                    (col_start + col) % self.width,
+                );
+                let target = isolated.get_index(row + margin, col + margin);
+                isolated.cells[target] = self.cells[source];
+            }
+        }
+        Some(isolated)
+    }
+
+    // The live cells as one object, in a form shared by every phase,
+    // rotation and reflection of it: each of the first `max_period` phases
+    // in isolation is turned all eight ways, and the smallest shape wins.
+    pub(crate) fn canonical_form(&self, max_period: u32) -> Vec<(u32, u32)> {
+        let max_period = max_period.min(MAX_CLASSIFY_PERIOD);
+        let mut isolated = match self.isolate(max_period + 1) {
+            Some(isolated) => isolated,
+            None => return Vec::new(),
+        };
+        let start = isolated.cells.clone();
+        let start_shape = isolated.shape();
+
+        let mut best: Option<Vec<(u32, u32)>> = None;
+        for _ in 0..max_period.max(1) {
+            let shape = isolated.shape();
+            let height = shape.iter().map(|&(row, _)| row).max().unwrap_or(0) + 1;
+            let width = shape.iter().map(|&(_, col)| col).max().unwrap_or(0) + 1;
+            for orientation in 0..8 {
+                let mut turned: Vec<(u32, u32)> = shape
+                    .iter()
+                    .map(|&(row, col)| search::orient(orientation, row, col, height, width))
+                    .collect();
+                turned.sort_unstable();
+                if best.as_ref().is_none_or(|best| turned < *best) {
+                    best = Some(turned);
+                }
+            }
+
+            isolated.step();
+            if isolated.cells == start || isolated.shape() == start_shape {
+                break;
+            }
+        }
+        best.unwrap_or_default()
+    }
+
+    // Live cell positions relative to their bounding box, for comparing
+    // patterns up to translation.
+    pub(crate) fn shape(&self) -> Vec<(u32, u32)> {
+        let cells: Vec<(u32, u32)> = (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.cells[self.get_index(row, col)] == Cell::Alive)
+            .collect();
+        let min_row = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+        let min_col = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+        cells
+            .into_iter()
+            .map(|(row, col)| (row - min_row, col - min_col))
+            .collect()
+    }
+}
+
+// The shortest circular run of `len` lines covering every occupied line,
+// as (start, length). It begins just after the longest run of empty lines.
+fn toroidal_extent(len: u32, occupied: impl Fn(u32) -> bool) -> Option<(u32, u32)> {
+    let occupied: Vec<bool> = (0..len).map(occupied).collect();
+    let first = occupied.iter().position(|&o| o)? as u32;
+
+    let (mut best_gap, mut best_start) = (0, first);
+    let mut gap = 0;
+    for step in 1..=len {
+        let line = (first + step) % len;
+        if occupied[line as usize] {
+            if gap > best_gap {
+                best_gap = gap;
+                best_start = line;
+            }
+            gap = 0;
+        } else {
+            gap += 1;
+        }
+    }
+    Some((best_start, len - best_gap))
+}
@@ -0,0 +1,80 @@
+//! Saving and restoring the automaton's configuration separately from the
+//! board, so settings can be shared without the cells.
+//!
+//! The boundary and neighbourhood are fixed for now; they are part of the
+//! format so saved configurations keep working once they become
+//! configurable, and anything else is rejected on import.
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::rules::Rule;
+use crate::{Cell, Error, Universe};
+
+pub(crate) const BOUNDARY: &str = "toroidal";
+const NEIGHBORHOOD: &str = "moore";
+
+#[derive(Serialize, Deserialize)]
+struct Config {
+    width: u32,
+    height: u32,
+    rule: String,
+    boundary: String,
+    neighborhood: String,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The configuration as JSON, e.g.
+    /// `{"width":64,"height":64,"rule":"B3/S23","boundary":"toroidal","neighborhood":"moore"}`.
+    pub fn config_to_json(&self) -> String {
+        let config = Config {
+            width: self.width,
+            height: self.height,
+            rule: self.rule.to_string(),
+            boundary: BOUNDARY.to_string(),
+            neighborhood: NEIGHBORHOOD.to_string(),
+        };
+        serde_json::to_string(&config).unwrap()
+    }
+
+    /// Applies a configuration produced by `config_to_json`, leaving the
+    /// universe untouched if any of it is invalid, including a size with no
+    /// cells or more than `MAX_CELLS`. Changing the dimensions clears the
+    /// board, as `set_width`/`set_height` do.
+    pub fn config_from_json(&mut self, json: &str) -> Result<(), Error> {
+        let config: Config =
+            serde_json::from_str(json).map_err(|e| Error::new(format!("invalid config: {}", e)))?;
+
+        let rule = Rule::parse(&config.rule)?;
+        for (field, value, supported) in [
+            ("boundary", &config.boundary, BOUNDARY),
+            ("neighborhood", &config.neighborhood, NEIGHBORHOOD),
+        ] {
+            if value != supported {
+                return Err(Error::new(format!(
+                    "unsupported {} {:?}, only {:?} is available",
+                    field, value, supported
+                )));
+            }
+        }
+
+        if config.width == 0 || config.height == 0 {
+            return Err(Error::new(format!(
+                "a {}x{} universe has no cells",
+                config.width, config.height
+            )));
+        }
+        let len = crate::checked_cell_count(config.width, config.height)?;
+
+        self.rule = rule;
+        self.history.drop_keyframes();
+        if (config.width, config.height) != (self.width, self.height) {
+            self.width = config.width;
+            self.height = config.height;
+            self.cells = vec![Cell::Dead; len];
+            self.resized();
+        }
+        Ok(())
+    }
+}
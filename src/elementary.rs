@@ -0,0 +1,137 @@
+//! One-dimensional elementary cellular automata drawn as space-time
+//! diagrams: each row of the universe holds one generation, and the next
+//! generation is drawn into the row below. Rules use Wolfram numbering.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+/// The next generation of `row` under elementary rule `rule`, wrapping at
+/// the ends of the row.
+pub(crate) fn next_generation(rule: u8, row: &[Cell]) -> Vec<Cell> {
+    let len = row.len();
+    (0..len)
+        .map(|i| {
+            let left = row[(i + len - 1) % len] as u8;
+            let center = row[i] as u8;
+            let right = row[(i + 1) % len] as u8;
+            if rule >> (left << 2 | center << 1 | right) & 1 == 1 {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            }
+        })
+        .collect()
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Clears the first `length` columns of `row` and sets the cell at their
+    /// centre alive, the classic single-cell start for Rule 110. Fails if
+    /// that leaves no columns, as on a board with no width.
+    pub fn add_rule110_initial_state(&mut self, row: u32, length: u32) -> Result<(), Error> {
+        let length = self.check_elementary_bounds(row, length)?;
+        if length == 0 {
+            return Err(Error::new("a Rule 110 start needs at least one column"));
+        }
+        let start = self.get_index(row, 0);
+        for cell in &mut self.cells[start..start + length as usize] {
+            *cell = Cell::Dead;
+        }
+        self.cells[start + length as usize / 2] = Cell::Alive;
+        self.cells_edited();
+        Ok(())
+    }
+
+    /// Treats `start_row` as the initial state of elementary automaton
+    /// `rule_number` and fills every row below it with successive
+    /// generations. The row wraps around toroidally.
+    pub fn embed_1d_ca(&mut self, rule_number: u8, start_row: u32) -> Result<(), Error> {
+        let width = self.check_elementary_bounds(start_row, self.width)?;
+        self.draw_elementary(rule_number, start_row, width);
+        Ok(())
+    }
+
+    /// Treats the first `length` columns of `start_row` as a Rule 110 state
+    /// and draws successive generations into every row below it. The segment
+    /// wraps around at its own ends.
+    pub fn evolve_rule110(&mut self, start_row: u32, length: u32) -> Result<(), Error> {
+        let length = self.check_elementary_bounds(start_row, length)?;
+        self.draw_elementary(110, start_row, length);
+        Ok(())
+    }
+
+    /// Ticks a one-row universe as elementary automaton `rule_number`
+    /// instead of Life: each cell looks at itself and its left and right
+    /// neighbours, wrapping at the ends of the row. Counts as a generation
+    /// for history and statistics.
+    pub fn as_row_rule(&mut self, rule_number: u8) -> Result<(), Error> {
+        if self.height != 1 {
+            return Err(Error::new(format!(
+                "as_row_rule needs a universe one row high, not {}",
+                self.height
+            )));
+        }
+
+        self.history.record(&self.cells);
+        self.history.drop_keyframes();
+        let next = next_generation(rule_number, &self.cells);
+        let (mut births, mut deaths) = (0, 0);
+        for (&before, &after) in self.cells.iter().zip(&next) {
+            match (before, after) {
+                (Cell::Dead, Cell::Alive) => births += 1,
+                (Cell::Alive, Cell::Dead) => deaths += 1,
+                _ => {}
+            }
+        }
+        self.finish_step(next, births, deaths);
+        Ok(())
+    }
+
+    /// Runs all 256 elementary automata from `initial_row`, for a rule
+    /// explorer. Each rule gets `generations` rows of `width` bytes, 1 for
+    /// alive and 0 for dead, starting with the initial row itself; rules
+    /// follow each other in order, so rule `r`'s generation `g` starts at
+    /// byte `(r * generations + g) * width`. The board is left untouched.
+    pub fn run_all_1d_rules(&self, initial_row: u32, generations: u32) -> Result<Box<[u8]>, Error> {
+        let width = self.check_elementary_bounds(initial_row, self.width)? as usize;
+        let start = self.get_index(initial_row, 0);
+        let initial = &self.cells[start..start + width];
+
+        let mut diagrams = Vec::with_capacity(256 * generations as usize * width);
+        for rule in 0..=255 {
+            let mut row = initial.to_vec();
+            for generation in 0..generations {
+                if generation > 0 {
+                    row = next_generation(rule, &row);
+                }
+                diagrams.extend(row.iter().map(|&cell| cell as u8));
+            }
+        }
+        Ok(diagrams.into_boxed_slice())
+    }
+}
+
+impl Universe {
+    // `length` clamped to the width, or an error if `row` is off the board.
+    fn check_elementary_bounds(&self, row: u32, length: u32) -> Result<u32, Error> {
+        if row >= self.height {
+            return Err(Error::new(format!(
+                "row {} is outside a universe of height {}",
+                row, self.height
+            )));
+        }
+        Ok(length.min(self.width))
+    }
+
+    pub(crate) fn draw_elementary(&mut self, rule: u8, start_row: u32, length: u32) {
+        let length = length as usize;
+        for row in start_row + 1..self.height {
+            let previous = self.get_index(row - 1, 0);
+            let current = self.get_index(row, 0);
+            let next = next_generation(rule, &self.cells[previous..previous + length]);
+            self.cells[current..current + length].copy_from_slice(&next);
+        }
+        self.cells_edited();
+    }
+}
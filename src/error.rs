@@ -0,0 +1,34 @@
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// The error returned by fallible `Universe` methods.
+///
+/// It stays a plain Rust value on native targets and only becomes a JS
+/// `Error` when it crosses the wasm boundary, so the error paths can be
+/// exercised by `cargo test`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Error {
+    pub(crate) fn new(message: impl Into<String>) -> Error {
+        Error(message.into())
+    }
+
+    pub fn message(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for JsValue {
+    fn from(error: Error) -> JsValue {
+        js_sys::Error::new(&error.0).into()
+    }
+}
@@ -0,0 +1,245 @@
+//! Undo/redo of generations.
+//!
+//! Every `tick` records the board it replaces. Undoing swaps the current
+//! board for the most recent recording and keeps it for redo; ticking or
+//! editing afterwards starts a new timeline and drops the redo states.
+//!
+//! Keyframes are boards saved at chosen generations by
+//! `precompute_keyframes`, so `seek` can jump further back than the undo
+//! history reaches. They belong to the timeline they were computed on and
+//! are dropped when the board is edited or resized, when the rule or the
+//! active region changes, and when a tick uses some other rule.
+
+use std::collections::{BTreeMap, VecDeque};
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+/// How many past generations are kept before the oldest is dropped.
+pub const HISTORY_LIMIT: usize = 100;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct History {
+    undo: VecDeque<Vec<Cell>>,
+    redo: Vec<Vec<Cell>>,
+    keyframes: BTreeMap<u32, Vec<Cell>>,
+}
+
+impl History {
+    pub(crate) fn record(&mut self, cells: &[Cell]) {
+        if self.undo.len() == HISTORY_LIMIT {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(cells.to_vec());
+        self.redo.clear();
+    }
+
+    pub(crate) fn clear_redo(&mut self) {
+        self.redo.clear();
+    }
+
+    // For edits, rule and region changes and one-off ticks under another
+    // rule, after which the board no longer follows the timeline the
+    // keyframes were computed on.
+    pub(crate) fn drop_keyframes(&mut self) {
+        self.keyframes.clear();
+    }
+
+    // For resizes, which make every recorded board the wrong shape.
+    pub(crate) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.keyframes.clear();
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Steps back one generation. Returns `false` if there is nothing to
+    /// undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo.pop_back() {
+            Some(previous) => {
+                let current = std::mem::replace(&mut self.cells, previous);
+                self.history.redo.push(current);
+                self.generation -= 1;
+                self.generations_since_change = 0;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-applies the most recently undone generation. Returns `false` if
+    /// there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo.pop() {
+            Some(next) => {
+                let current = std::mem::replace(&mut self.cells, next);
+                self.history.undo.push_back(current);
+                self.generation += 1;
+                self.generations_since_change = 0;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of boards stored for undo and redo, not counting the current
+    /// one.
+    pub fn history_len(&self) -> usize {
+        self.history.undo.len() + self.history.redo.len()
+    }
+
+    /// How many undos deep the current board is; 0 unless something has
+    /// been undone since the last tick or edit.
+    pub fn history_position(&self) -> usize {
+        self.history.redo.len()
+    }
+
+    /// Saves the board at every generation from now to `up_to` that is a
+    /// multiple of `every_n`, computed on a copy, for `seek` to start from.
+    /// The board itself is not changed. The generation counter is a `u32`,
+    /// so nothing past `u32::MAX` is saved.
+    pub fn precompute_keyframes(&mut self, every_n: u64, up_to: u64) -> Result<(), Error> {
+        if every_n == 0 {
+            return Err(Error::new("keyframes need a spacing of at least 1"));
+        }
+
+        let up_to = up_to.min(u32::MAX as u64) as u32;
+        let mut copy = self.scratch();
+        while copy.generation <= up_to {
+            if (copy.generation as u64).is_multiple_of(every_n) {
+                self.history
+                    .keyframes
+                    .insert(copy.generation, copy.cells.clone());
+            }
+            if copy.generation == up_to {
+                break;
+            }
+            copy.step();
+        }
+        Ok(())
+    }
+
+    /// Moves the board to `target_generation` and returns `true`, or returns
+    /// `false` and leaves it alone if that generation can't be reached.
+    /// Later generations are always reachable; earlier ones within the undo
+    /// history are undone to, and beyond that the board ticks forward from
+    /// the nearest keyframe before the target, if there is one. A jump to a
+    /// keyframe clears the undo history, which belonged to the old position.
+    /// A target past `u32::MAX`, where the generation counter ends, is never
+    /// reachable.
+    pub fn seek(&mut self, target_generation: u64) -> bool {
+        if target_generation > u32::MAX as u64 {
+            return false;
+        }
+        let target_generation = target_generation as u32;
+        if target_generation < self.generation {
+            let back = (self.generation - target_generation) as usize;
+            if back <= self.history.undo.len() {
+                for _ in 0..back {
+                    self.undo();
+                }
+                return true;
+            }
+        }
+
+        let keyframe = self
+            .history
+            .keyframes
+            .range(..=target_generation)
+            .next_back()
+            .filter(|&(&generation, _)| {
+                generation > self.generation || target_generation < self.generation
+            })
+            .map(|(&generation, cells)| (generation, cells.clone()));
+        match keyframe {
+            Some((generation, cells)) => {
+                self.history.undo.clear();
+                self.history.redo.clear();
+                self.cells = cells;
+                self.generation = generation;
+                self.generations_since_change = 0;
+            }
+            None if target_generation < self.generation => return false,
+            None => {}
+        }
+        while self.generation < target_generation {
+            self.tick();
+        }
+        true
+    }
+
+    /// Number of cells that differ from the previous generation kept in the
+    /// history, which after a plain tick is its births plus deaths. 0 when
+    /// there is no previous generation, as on a new or resized board.
+    pub fn churn(&self) -> u32 {
+        match self.history.undo.back() {
+            Some(previous) => previous
+                .iter()
+                .zip(&self.cells)
+                .filter(|(before, now)| before != now)
+                .count() as u32,
+            None => 0,
+        }
+    }
+}
+
+impl Universe {
+    /// Ticks `frames` times, returning for each tick the sorted flat indices
+    /// of the cells it changed. On a slowly changing board this is far
+    /// smaller than a copy of every generation; `replay_deltas` turns it
+    /// back into boards.
+    pub fn record_deltas(&mut self, frames: u32) -> Vec<Vec<u32>> {
+        (0..frames)
+            .map(|_| {
+                let before = self.cells.clone();
+                self.tick();
+                before
+                    .iter()
+                    .zip(&self.cells)
+                    .enumerate()
+                    .filter(|(_, (before, now))| before != now)
+                    .map(|(idx, _)| idx as u32)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The generations recorded by `record_deltas`, rebuilt by flipping the
+    /// changed cells starting from this board, which should be the one the
+    /// recording started from. This universe is left untouched.
+    pub fn replay_deltas(&self, deltas: &[Vec<u32>]) -> Result<Vec<Universe>, Error> {
+        let mut current = self.clone();
+        current.history.clear();
+        deltas
+            .iter()
+            .map(|delta| {
+                if let Some(&idx) = delta
+                    .iter()
+                    .find(|&&idx| idx as usize >= current.cells.len())
+                {
+                    return Err(Error::new(format!(
+                        "cell {} is outside a {}x{} universe",
+                        idx, current.width, current.height
+                    )));
+                }
+                for &idx in delta {
+                    let cell = &mut current.cells[idx as usize];
+                    *cell = match *cell {
+                        Cell::Dead => Cell::Alive,
+                        Cell::Alive => Cell::Dead,
+                    };
+                }
+                current.generation += 1;
+                if delta.is_empty() {
+                    current.generations_since_change += 1;
+                } else {
+                    current.generations_since_change = 0;
+                }
+                Ok(current.clone())
+            })
+            .collect()
+    }
+}
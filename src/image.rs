@@ -0,0 +1,103 @@
+//! Seeding a universe from raster images.
+//!
+//! Each pixel becomes one cell: pixels whose luminance is below `threshold`
+//! are alive, so dark shapes on a light background come out as live cells.
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{Cell, Error, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Builds a `width` x `height` universe from RGBA pixel data, such as the
+    /// `data` of a canvas `ImageData`. Alpha is ignored. Images of more than
+    /// `MAX_CELLS` pixels are rejected.
+    pub fn from_image_data(
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+        threshold: u8,
+    ) -> Result<Universe, Error> {
+        let expected = crate::checked_cell_count(width, height)?
+            .checked_mul(4)
+            .ok_or_else(|| Error::new(format!("a {}x{} image is too large", width, height)))?;
+        if rgba.len() != expected {
+            return Err(Error::new(format!(
+                "expected {} bytes of RGBA data for a {}x{} image, got {}",
+                expected,
+                width,
+                height,
+                rgba.len()
+            )));
+        }
+
+        let mut universe = Universe::empty(width, height);
+        for (cell, pixel) in universe.cells.iter_mut().zip(rgba.chunks_exact(4)) {
+            let luminance =
+                0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64;
+            if luminance < threshold as f64 {
+                *cell = Cell::Alive;
+            }
+        }
+        Ok(universe)
+    }
+
+    /// Fetches the image at `url` and builds a universe from its pixels as
+    /// `from_image_data` does. The returned promise rejects with a
+    /// descriptive error if the request fails (including CORS rejections) or
+    /// the response isn't a decodable image.
+    pub async fn from_image_url(url: String, threshold: u8) -> Result<Universe, Error> {
+        let window = web_sys::window().ok_or_else(|| Error::new("no window to fetch from"))?;
+
+        let response = JsFuture::from(window.fetch_with_str(&url))
+            .await
+            .map_err(|_| {
+                Error::new(format!(
+                    "could not fetch {}: network error or blocked by CORS",
+                    url
+                ))
+            })?
+            .unchecked_into::<web_sys::Response>();
+        if !response.ok() {
+            return Err(Error::new(format!(
+                "fetching {} failed with status {}",
+                url,
+                response.status()
+            )));
+        }
+
+        let not_an_image = |_| Error::new(format!("{} is not a decodable image", url));
+        let blob = JsFuture::from(response.blob().map_err(not_an_image)?)
+            .await
+            .map_err(not_an_image)?
+            .unchecked_into::<web_sys::Blob>();
+        let bitmap = JsFuture::from(
+            window
+                .create_image_bitmap_with_blob(&blob)
+                .map_err(not_an_image)?,
+        )
+        .await
+        .map_err(not_an_image)?
+        .unchecked_into::<web_sys::ImageBitmap>();
+
+        let (width, height) = (bitmap.width(), bitmap.height());
+        let no_canvas = |_| Error::new("could not create a canvas to read the image");
+        let context = web_sys::OffscreenCanvas::new(width, height)
+            .map_err(no_canvas)?
+            .get_context("2d")
+            .map_err(no_canvas)?
+            .ok_or_else(|| no_canvas(JsValue::NULL))?
+            .unchecked_into::<web_sys::OffscreenCanvasRenderingContext2d>();
+        context
+            .draw_image_with_image_bitmap(&bitmap, 0.0, 0.0)
+            .map_err(not_an_image)?;
+        let pixels = context
+            .get_image_data(0.0, 0.0, width as f64, height as f64)
+            .map_err(not_an_image)?
+            .data();
+
+        Universe::from_image_data(width, height, &pixels, threshold)
+    }
+}
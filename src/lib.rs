@@ -1,5 +1,51 @@
+mod analysis;
+mod builder;
+mod census;
+mod classify;
+mod config;
+mod elementary;
+mod error;
+mod history;
+mod image;
+mod lz77;
+mod margolus;
+mod metadata;
+mod overlay;
+mod pacing;
+mod patterns;
+mod profile;
+mod qr;
+mod random;
+mod rle;
+mod rules;
+mod search;
+#[cfg(feature = "serde")]
+mod serialize;
+mod simd;
+mod stats;
+mod tape;
+mod texture;
 mod utils;
-
+mod webp;
+
+pub use analysis::Symmetry;
+pub use builder::UniverseBuilder;
+pub use census::CensusReport;
+pub use classify::{PatternKind, MAX_CLASSIFY_PERIOD};
+pub use error::Error;
+pub use history::HISTORY_LIMIT;
+pub use overlay::BlendMode;
+pub use patterns::{
+    Axis, COPPERHEAD, EATER_1, GLIDER, GOSPER_GLIDER_GUN, HERSCHEL, INFINITE_GROWTH,
+    P14_OSCILLATOR, P60_OSCILLATOR, PULSAR, QUEEN_BEE_SHUTTLE, SWITCH_ENGINE, WEEKENDER,
+};
+pub use search::MAX_STILL_LIFE_CELLS;
+pub use stats::TickStats;
+
+use history::History;
+use pacing::Pacer;
+use rules::Rule;
+use std::collections::HashMap;
 use std::fmt;
 use wasm_bindgen::prelude::*;
 
@@ -10,25 +56,47 @@ use wasm_bindgen::prelude::*;
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 // A macro to provide `println!(..)`-style syntax for `console.log` logging.
+#[allow(unused_macros)]
 macro_rules! log {
     ( $( $t:tt )* ) => {
         web_sys::console::log_1(&format!( $( $t )* ).into());
     }
 }
 
+/// The most cells a universe read from a pattern file or other untrusted
+/// input may have.
+pub const MAX_CELLS: u32 = 1 << 26;
+
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cell {
+    #[default]
     Dead = 0,
     Alive = 1,
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Universe {
     width: u32,
     height: u32,
     cells: Vec<Cell>,
+    generation: u32,
+    generations_since_change: u32,
+    margolus_rule: [u8; 16],
+    // (row, col, height, width) of the only cells `tick` updates, clamped to
+    // the board; `None` updates everything.
+    active_region: Option<(u32, u32, u32, u32)>,
+    history: History,
+    pacer: Pacer,
+    rule: Rule,
+    // Rules saved by `push_rule`, most recent last.
+    rule_stack: Vec<Rule>,
+    seed: Option<u64>,
+    // Everything but the rule, which `get_metadata` reads from `rule`.
+    metadata: HashMap<String, String>,
 }
 
 impl Universe {
@@ -41,6 +109,96 @@ impl Universe {
             let idx = self.get_index(row, col);
             self.cells[idx] = Cell::Alive;
         }
+        self.cells_edited();
+    }
+
+    /// Replaces the whole board: each cell is alive where `f(row, col)` is
+    /// true and dead elsewhere.
+    pub fn reset_with<F: Fn(u32, u32) -> bool>(&mut self, f: F) {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = self.get_index(row, col);
+                self.cells[idx] = if f(row, col) { Cell::Alive } else { Cell::Dead };
+            }
+        }
+        self.cells_edited();
+    }
+
+    /// Packs the grid row by row into `buf`, one bit per cell with the first
+    /// cell in the most significant bit of each word. Bits past the last cell
+    /// are cleared.
+    pub fn pack_cells_into_bitset(&self, buf: &mut [u64]) -> Result<(), Error> {
+        check_bitset_len(self.width, self.height, buf.len())?;
+        buf.iter_mut().for_each(|word| *word = 0);
+        for (idx, &cell) in self.cells.iter().enumerate() {
+            buf[idx / 64] |= (cell as u64) << (63 - idx % 64);
+        }
+        Ok(())
+    }
+
+    /// The inverse of `pack_cells_into_bitset`.
+    pub fn unpack_from_bitset(buf: &[u64], width: u32, height: u32) -> Result<Universe, Error> {
+        check_bitset_len(width, height, buf.len())?;
+        let mut universe = Universe::empty(width, height);
+
+        for (idx, cell) in universe.cells.iter_mut().enumerate() {
+            if buf[idx / 64] >> (63 - idx % 64) & 1 == 1 {
+                *cell = Cell::Alive;
+            }
+        }
+        Ok(universe)
+    }
+
+    /// The live cells as a compressed sparse row matrix, `(row_offsets,
+    /// column_indices)`; see `csr_row_offsets`.
+    pub fn to_csr(&self) -> (Box<[u32]>, Box<[u32]>) {
+        let mut row_offsets = Vec::with_capacity(self.height as usize + 1);
+        let mut column_indices = Vec::new();
+        row_offsets.push(0);
+        for row in 0..self.height {
+            let start = self.get_index(row, 0);
+            let line = &self.cells[start..start + self.width as usize];
+            column_indices.extend((0..self.width).filter(|&col| line[col as usize] == Cell::Alive));
+            row_offsets.push(column_indices.len() as u32);
+        }
+        (
+            row_offsets.into_boxed_slice(),
+            column_indices.into_boxed_slice(),
+        )
+    }
+
+    /// Sets the cells at `offsets` from (`row`, `col`) alive, wrapping around
+    /// the edges. Any `i32` position is accepted; the arithmetic is done in
+    /// `i64` so extreme coordinates still wrap to the correct cell. A pattern
+    /// larger than the board wraps onto itself, and on a board with no cells
+    /// nothing is placed.
+    pub fn add_pattern(&mut self, row: i32, col: i32, offsets: &[(i32, i32)]) {
+        self.stamp(row, col, offsets, Cell::Alive);
+    }
+
+    /// The dual of `add_pattern`: sets the cells at `offsets` from (`row`,
+    /// `col`) dead, wrapping the same way, to carve a pattern-shaped hole.
+    pub fn erase_pattern(&mut self, row: i32, col: i32, offsets: &[(i32, i32)]) {
+        self.stamp(row, col, offsets, Cell::Dead);
+    }
+
+    fn stamp(&mut self, row: i32, col: i32, offsets: &[(i32, i32)], cell: Cell) {
+        // Nowhere to wrap to on a board with no cells.
+        if self.cells.is_empty() {
+            return;
+        }
+        for &(delta_row, delta_col) in offsets {
+            let idx =
+                self.wrapped_index(row as i64 + delta_row as i64, col as i64 + delta_col as i64);
+            self.cells[idx] = cell;
+        }
+        self.cells_edited();
+    }
+
+    fn wrapped_index(&self, row: i64, column: i64) -> usize {
+        let row = row.rem_euclid(self.height as i64) as u32;
+        let column = column.rem_euclid(self.width as i64) as u32;
+        self.get_index(row, column)
     }
 
     fn get_index(&self, row: u32, column: u32) -> usize {
@@ -49,9 +207,14 @@ impl Universe {
 
     fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
         let mut count = 0;
-        for delta_row in [self.height - 1, 0, 1].iter().cloned() {
-            for delta_col in [self.height - 1, 0, 1].iter().cloned() {
-                if delta_row == 0 && delta_col == 0 {
+        // On a board one cell high or wide, "one up" and "one down" are the
+        // same row (or column) as the cell itself; the neighbour is still
+        // counted, just as on a two-cell board both sides are counted.
+        let row_deltas = [self.height - 1, 0, 1];
+        let col_deltas = [self.width - 1, 0, 1];
+        for (i, &delta_row) in row_deltas.iter().enumerate() {
+            for (j, &delta_col) in col_deltas.iter().enumerate() {
+                if i == 1 && j == 1 {
                     continue;
                 }
 
@@ -63,43 +226,273 @@ impl Universe {
         }
         count
     }
+
+    // Call after any direct edit of the cells: the board is no longer idle
+    // and a redo would clobber the edit.
+    fn cells_edited(&mut self) {
+        self.generations_since_change = 0;
+        self.history.clear_redo();
+        self.history.drop_keyframes();
+    }
+
+    // Call after changing the dimensions: recorded boards and the active
+    // region no longer fit.
+    fn resized(&mut self) {
+        self.history.clear();
+        self.active_region = None;
+        self.cells_edited();
+    }
+
+    // The rows and columns `tick` updates.
+    fn active_bounds(&self) -> (std::ops::Range<u32>, std::ops::Range<u32>) {
+        match self.active_region {
+            Some((row, col, height, width)) => (row..row + height, col..col + width),
+            None => (0..self.height, 0..self.width),
+        }
+    }
+
+    // A copy to tick without touching this universe: the board, rule and
+    // generation counters, but none of the undo history, keyframes or
+    // other per-session state, which would be expensive to copy and unused.
+    pub(crate) fn scratch(&self) -> Universe {
+        Universe {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.clone(),
+            generation: self.generation,
+            generations_since_change: self.generations_since_change,
+            margolus_rule: self.margolus_rule,
+            active_region: self.active_region,
+            history: History::default(),
+            pacer: Pacer::default(),
+            rule: self.rule,
+            rule_stack: Vec::new(),
+            seed: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    // Advances one generation and returns the number of (births, deaths).
+    fn step(&mut self) -> (u32, u32) {
+        self.step_with(Universe::next_state)
+    }
+
+    // Like `step`, deciding each cell's fate with `rule` instead of
+    // `next_state`. The rule must never bring a cell with no live
+    // neighbours to life.
+    fn step_with(&mut self, rule: impl Fn(&Universe, Cell, u8) -> Cell) -> (u32, u32) {
+        // Nothing is born from an all-dead neighbourhood, so an empty board
+        // only needs its bookkeeping updated.
+        if self.cells.iter().all(|&cell| cell == Cell::Dead) {
+            let next = std::mem::take(&mut self.cells);
+            return self.finish_step(next, 0, 0);
+        }
+
+        let mut next = self.cells.clone();
+        let mut births = 0;
+        let mut deaths = 0;
+        self.for_each_transition(&rule, |idx, cell, next_cell| {
+            match (cell, next_cell) {
+                (Cell::Dead, Cell::Alive) => births += 1,
+                (Cell::Alive, Cell::Dead) => deaths += 1,
+                _ => {}
+            }
+            next[idx] = next_cell;
+        });
+
+        self.finish_step(next, births, deaths)
+    }
+
+    /// The flat indices of the cells the next `tick` would bring to life and
+    /// kill, without changing the board.
+    pub fn preview_next(&self) -> (Vec<u32>, Vec<u32>) {
+        let mut births = Vec::new();
+        let mut deaths = Vec::new();
+        if self.cells.contains(&Cell::Alive) {
+            self.for_each_transition(&Universe::next_state, |idx, cell, next_cell| {
+                match (cell, next_cell) {
+                    (Cell::Dead, Cell::Alive) => births.push(idx as u32),
+                    (Cell::Alive, Cell::Dead) => deaths.push(idx as u32),
+                    _ => {}
+                }
+            });
+        }
+        (births, deaths)
+    }
+
+    // Calls `f(index, current, next)` for every cell `tick` would update,
+    // with `next` decided by `rule`.
+    fn for_each_transition(
+        &self,
+        rule: &impl Fn(&Universe, Cell, u8) -> Cell,
+        mut f: impl FnMut(usize, Cell, Cell),
+    ) {
+        let (rows, cols) = self.active_bounds();
+        for row in rows {
+            for col in cols.clone() {
+                let idx = self.get_index(row, col);
+                let cell = self.cells[idx];
+                let live_neighbors = self.live_neighbor_count(row, col);
+                f(idx, cell, rule(self, cell, live_neighbors));
+            }
+        }
+    }
+
+    // Installs `next` as the new generation and updates the bookkeeping
+    // shared by every tick implementation.
+    fn finish_step(&mut self, next: Vec<Cell>, births: u32, deaths: u32) -> (u32, u32) {
+        if births == 0 && deaths == 0 {
+            self.generations_since_change += 1;
+        } else {
+            self.generations_since_change = 0;
+        }
+        self.history.clear_redo();
+        self.generation += 1;
+        self.cells = next;
+        (births, deaths)
+    }
 }
 
 // Public methods, exported to javascript
 #[wasm_bindgen]
 impl Universe {
     pub fn new() -> Universe {
-        let width = 64;
-        let height = 64;
-        let cells: Vec<Cell> = (0..width * height)
-            .map(|_i| {
-                if js_sys::Math::random() > 0.5 {
-                    Cell::Dead
-                } else {
-                    Cell::Alive
-                }
-            })
-            .collect();
+        utils::set_panic_hook();
+
+        let mut universe = Universe::empty(64, 64);
+        universe.reset();
+        universe
+    }
 
+    /// A `width` x `height` universe with every cell dead.
+    pub fn empty(width: u32, height: u32) -> Universe {
         Universe {
             width,
             height,
-            cells,
+            cells: vec![Cell::default(); (width * height) as usize],
+            generation: 0,
+            generations_since_change: 0,
+            margolus_rule: margolus::IDENTITY,
+            active_region: None,
+            history: History::default(),
+            pacer: Pacer::default(),
+            rule: Rule::default(),
+            rule_stack: Vec::new(),
+            seed: None,
+            metadata: HashMap::new(),
         }
     }
 
+    /// Changes the width and clears the board. The generation counter
+    /// carries on from where it was rather than going back to 0, as it does
+    /// for every resize.
     pub fn set_width(&mut self, width: u32) {
         self.width = width;
         self.cells = (0..width * self.height).map(|_i| Cell::Dead).collect();
+        self.resized();
     }
 
+    /// Changes the height and clears the board, keeping the generation
+    /// counter like `set_width`.
     pub fn set_height(&mut self, height: u32) {
         self.height = height;
         self.cells = (0..self.width * height).map(|_i| Cell::Dead).collect();
+        self.resized();
+    }
+
+    /// Changes the dimensions, keeping the cells that still fit: the board
+    /// is cropped or extended with dead cells at the bottom and right. The
+    /// generation counter is kept, as for `set_width`.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let mut cells = vec![Cell::Dead; (width * height) as usize];
+        let kept = width.min(self.width) as usize;
+        for (row, line) in self
+            .cells
+            .chunks(self.width as usize)
+            .take(height as usize)
+            .enumerate()
+        {
+            let start = row * width as usize;
+            cells[start..start + kept].copy_from_slice(&line[..kept]);
+        }
+
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+        self.resized();
+    }
+
+    /// Grows the board by `margin` dead cells on every side, keeping the
+    /// existing cells where they were relative to each other. Fails if the
+    /// grown board would be too large to address.
+    pub fn pad(&mut self, margin: u32) -> Result<(), Error> {
+        let too_large = || Error::new(format!("padding by {} makes the board too large", margin));
+        let grown = |side: u32| {
+            margin
+                .checked_mul(2)
+                .and_then(|both| side.checked_add(both))
+                .ok_or_else(too_large)
+        };
+        let width = grown(self.width)?;
+        let height = grown(self.height)?;
+        let len = (width as usize)
+            .checked_mul(height as usize)
+            .ok_or_else(too_large)?;
+        let mut cells = vec![Cell::Dead; len];
+        if !self.cells.is_empty() {
+            for (row, line) in self.cells.chunks(self.width as usize).enumerate() {
+                let start = (row + margin as usize) * width as usize + margin as usize;
+                cells[start..start + line.len()].copy_from_slice(line);
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+        self.resized();
+        Ok(())
+    }
+
+    /// Restricts `tick` to the `height` x `width` rectangle at (`row`,
+    /// `col`), clamped to the board. Cells outside it are frozen but still
+    /// count as neighbours of the cells inside. A rectangle covering the
+    /// whole board restores normal ticking. Resizing the board clears it.
+    /// Keyframes were computed under the old region, so they are dropped.
+    pub fn set_active_region(&mut self, row: u32, col: u32, height: u32, width: u32) {
+        let row = row.min(self.height);
+        let col = col.min(self.width);
+        let height = height.min(self.height - row);
+        let width = width.min(self.width - col);
+        self.active_region = if (height, width) == (self.height, self.width) {
+            None
+        } else {
+            Some((row, col, height, width))
+        };
+        self.history.drop_keyframes();
     }
 
     pub fn kill(&mut self) {
         self.cells = (0..self.width * self.height).map(|_i| Cell::Dead).collect();
+        self.cells_edited();
+    }
+
+    /// Kills every cell within `thickness` cells of an edge, so a pattern
+    /// in the middle can be studied without anything wrapping into it. A
+    /// thickness of half the board or more clears everything.
+    pub fn clear_border(&mut self, thickness: u32) {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let from_edge = row
+                    .min(self.height - 1 - row)
+                    .min(col)
+                    .min(self.width - 1 - col);
+                if from_edge < thickness {
+                    let idx = self.get_index(row, col);
+                    self.cells[idx] = Cell::Dead;
+                }
+            }
+        }
+        self.cells_edited();
     }
 
     pub fn reset(&mut self) {
@@ -112,12 +505,43 @@ impl Universe {
                 }
             })
             .collect();
+        self.seed = None;
+        self.cells_edited();
     }
 
     pub fn render(&self) -> String {
         self.to_string()
     }
 
+    /// Like `render`, with a frame of `border` around the grid marking where
+    /// it wraps around: one extra row above and below and one extra column
+    /// on each side.
+    pub fn render_bordered(&self, border: char) -> String {
+        let edge: String = std::iter::repeat_n(border, self.width as usize + 2).collect();
+        let mut text = format!("{}\n", edge);
+        for line in self.to_string().lines() {
+            text.push(border);
+            text.push_str(line);
+            text.push(border);
+            text.push('\n');
+        }
+        text + &edge + "\n"
+    }
+
+    /// Like `render`, but each cell shows its live-neighbour count (0-8)
+    /// instead of its state, one digit per cell and one line per row.
+    pub fn display_neighbor_counts(&self) -> String {
+        let mut text = String::with_capacity(((self.width + 1) * self.height) as usize);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let count = self.live_neighbor_count(row, col);
+                text.push(char::from(b'0' + count));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -126,109 +550,369 @@ impl Universe {
         self.height
     }
 
+    /// Number of ticks since the universe was created.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Number of live cells.
+    pub fn population(&self) -> u32 {
+        self.cells
+            .iter()
+            .filter(|&&cell| cell == Cell::Alive)
+            .count() as u32
+    }
+
+    /// Number of consecutive ticks that left the board unchanged. Any edit
+    /// or changing tick resets it to 0.
+    pub fn generations_since_change(&self) -> u32 {
+        self.generations_since_change
+    }
+
     pub fn cells(&self) -> *const Cell {
         self.cells.as_ptr()
     }
 
+    /// The grid as an array of rows, each an array of 0s and 1s, for quick
+    /// scripts. Every cell becomes a separate JS number, so this is much
+    /// slower than reading `cells()` from wasm memory; don't call it every
+    /// frame on a large board.
+    pub fn cells_2d(&self) -> js_sys::Array {
+        self.cells
+            .chunks(self.width as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|&cell| JsValue::from(cell as u8))
+                    .collect::<js_sys::Array>()
+            })
+            .collect()
+    }
+
+    /// The row offsets of the board in compressed sparse row form: the
+    /// columns of row `i`'s live cells are `csr_column_indices()[offsets[i]
+    /// ..offsets[i + 1]]`. There are `height() + 1` offsets.
+    pub fn csr_row_offsets(&self) -> Box<[u32]> {
+        self.to_csr().0
+    }
+
+    /// The columns of every live cell, row by row and left to right within
+    /// each row; see `csr_row_offsets`.
+    pub fn csr_column_indices(&self) -> Box<[u32]> {
+        self.to_csr().1
+    }
+
+    /// The grid row by row, eight cells per byte in little-endian bit order:
+    /// cell `i` is bit `i % 8` (value `1 << (i % 8)`) of byte `i / 8`. Bits
+    /// past the last cell are 0.
+    pub fn cells_packed(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; self.cells.len().div_ceil(8)];
+        for (idx, &cell) in self.cells.iter().enumerate() {
+            bytes[idx / 8] |= (cell as u8) << (idx % 8);
+        }
+        bytes
+    }
+
+    /// The inverse of `cells_packed`. `bytes` must hold exactly
+    /// `ceil(width * height / 8)` bytes; bits past the last cell are ignored.
+    /// Fails for boards of more than `MAX_CELLS` cells.
+    pub fn from_packed(width: u32, height: u32, bytes: &[u8]) -> Result<Universe, Error> {
+        let expected = checked_cell_count(width, height)?.div_ceil(8);
+        if bytes.len() != expected {
+            return Err(Error::new(format!(
+                "a {}x{} universe packs into {} bytes, got {}",
+                width,
+                height,
+                expected,
+                bytes.len()
+            )));
+        }
+
+        let mut universe = Universe::empty(width, height);
+        for (idx, cell) in universe.cells.iter_mut().enumerate() {
+            if bytes[idx / 8] >> (idx % 8) & 1 == 1 {
+                *cell = Cell::Alive;
+            }
+        }
+        Ok(universe)
+    }
+
+    /// Sets the first 64 cells of `row` from `bitmask`, most significant
+    /// bit first: bit 63 is column 0 and bit 0 is column 63. Bits past the
+    /// end of a narrower row are ignored; on a wider row the other cells are
+    /// left alone. `row` must be on the board.
+    pub fn set_row_from_bitmask(&mut self, row: u32, bitmask: u64) {
+        let start = self.get_index(row, 0);
+        let len = self.width.min(64) as usize;
+        for (bit, cell) in self.cells[start..start + len].iter_mut().enumerate() {
+            *cell = if bitmask >> (63 - bit) & 1 == 1 {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            };
+        }
+        self.cells_edited();
+    }
+
+    /// Sets the whole of `row` from `ceil(width / 64)` bitmasks, each
+    /// covering the next 64 columns as in `set_row_from_bitmask`.
+    pub fn set_row_from_bitmasks(&mut self, row: u32, bitmasks: &[u64]) -> Result<(), Error> {
+        let words = self.width.div_ceil(64) as usize;
+        if bitmasks.len() != words {
+            return Err(Error::new(format!(
+                "a row of {} cells needs {} bitmasks, got {}",
+                self.width,
+                words,
+                bitmasks.len()
+            )));
+        }
+
+        let start = self.get_index(row, 0);
+        let line = &mut self.cells[start..start + self.width as usize];
+        for (col, cell) in line.iter_mut().enumerate() {
+            *cell = if bitmasks[col / 64] >> (63 - col % 64) & 1 == 1 {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            };
+        }
+        self.cells_edited();
+        Ok(())
+    }
+
+    /// The first 64 cells of `row` as a bitmask, laid out as in
+    /// `set_row_from_bitmask`. Bits past the end of a narrower row are 0.
+    pub fn get_row_as_bitmask(&self, row: u32) -> u64 {
+        self.get_row_as_bitmasks(row).first().copied().unwrap_or(0)
+    }
+
+    /// The whole of `row` as bitmasks, the inverse of
+    /// `set_row_from_bitmasks`.
+    pub fn get_row_as_bitmasks(&self, row: u32) -> Box<[u64]> {
+        let start = self.get_index(row, 0);
+        let line = &self.cells[start..start + self.width as usize];
+        let mut bitmasks = vec![0u64; line.len().div_ceil(64)];
+        for (col, &cell) in line.iter().enumerate() {
+            bitmasks[col / 64] |= (cell as u64) << (63 - col % 64);
+        }
+        bitmasks.into_boxed_slice()
+    }
+
     pub fn toggle_cell(&mut self, row: u32, col: u32) {
         let idx = self.get_index(row, col);
         self.cells[idx].toggle();
+        self.cells_edited();
     }
 
-    pub fn add_glider(&mut self, row: i32, col: i32) {
-        let cell_offsets = [[-1, 0], [0, 1], [1, -1], [1, 0], [1, 1]];
+    /// The live cells row by row as interleaved `[row0, col0, row1, col1,
+    /// ...]`.
+    pub fn alive_cells_flat(&self) -> Box<[u32]> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, &cell)| cell == Cell::Alive)
+            .flat_map(|(idx, _)| {
+                let idx = idx as u32;
+                [idx / self.width, idx % self.width]
+            })
+            .collect()
+    }
 
-        for [x, y] in cell_offsets {
-            let xx = (row + x).rem_euclid(self.height as i32);
-            let yy = (col + y).rem_euclid(self.width as i32);
+    /// Sets (`rows[i]`, `cols[i]`) alive for every `i`. Fails, leaving the
+    /// board untouched, if the arrays differ in length or any cell is off
+    /// the board.
+    pub fn add_from_coordinate_arrays(&mut self, rows: &[u32], cols: &[u32]) -> Result<(), Error> {
+        if rows.len() != cols.len() {
+            return Err(Error::new(format!(
+                "{} rows but {} columns",
+                rows.len(),
+                cols.len()
+            )));
+        }
+        if let Some((row, col)) = rows
+            .iter()
+            .zip(cols)
+            .find(|&(&row, &col)| row >= self.height || col >= self.width)
+        {
+            return Err(Error::new(format!(
+                "({}, {}) is outside the {}x{} board",
+                row, col, self.width, self.height
+            )));
+        }
 
-            let idx = self.get_index(xx as u32, yy as u32);
+        for (&row, &col) in rows.iter().zip(cols) {
+            let idx = self.get_index(row, col);
             self.cells[idx] = Cell::Alive;
         }
+        self.cells_edited();
+        Ok(())
     }
 
-    pub fn add_pulsar(&mut self, row: i32, col: i32) {
-        let cell_offsets = [
-            [-6, -4],
-            [-6, -3],
-            [-6, -2],
-            [-6, 2],
-            [-6, 3],
-            [-6, 4],
-            [-4, -6],
-            [-4, -1],
-            [-4, 1],
-            [-4, 6],
-            [-3, -6],
-            [-3, -1],
-            [-3, 1],
-            [-3, 6],
-            [-2, -6],
-            [-2, -1],
-            [-2, 1],
-            [-2, 6],
-            [-1, -4],
-            [-1, -3],
-            [-1, -2],
-            [-1, 2],
-            [-1, 3],
-            [-1, 4],
-            [6, -4],
-            [6, -3],
-            [6, -2],
-            [6, 2],
-            [6, 3],
-            [6, 4],
-            [4, -6],
-            [4, -1],
-            [4, 1],
-            [4, 6],
-            [3, -6],
-            [3, -1],
-            [3, 1],
-            [3, 6],
-            [2, -6],
-            [2, -1],
-            [2, 1],
-            [2, 6],
-            [1, -4],
-            [1, -3],
-            [1, -2],
-            [1, 2],
-            [1, 3],
-            [1, 4],
-        ];
-
-        for [x, y] in cell_offsets {
-            let xx = (row + x).rem_euclid(self.height as i32);
-            let yy = (col + y).rem_euclid(self.width as i32);
-
-            let idx = self.get_index(xx as u32, yy as u32);
-            self.cells[idx] = Cell::Alive;
+    /// Top-left corner of the cell at (`row`, `col`) on a canvas where each
+    /// cell is `cell_size` pixels square. Returns `[x, y]`.
+    pub fn grid_to_canvas(row: f64, col: f64, cell_size: f64) -> Box<[f64]> {
+        Box::new([col * cell_size, row * cell_size])
+    }
+
+    /// The cell containing canvas point (`x`, `y`). Returns `[row, col]`.
+    /// Fractional positions floor to the containing cell; negative ones
+    /// saturate to 0.
+    pub fn canvas_to_grid(x: f64, y: f64, cell_size: f64) -> Box<[u32]> {
+        Box::new([
+            (y / cell_size).floor() as u32,
+            (x / cell_size).floor() as u32,
+        ])
+    }
+
+    /// Paints a freehand stroke: `screen_coords` holds canvas points `[x0,
+    /// y0, x1, y1, ...]` from successive mouse events, and every cell on a
+    /// straight line between consecutive points is set alive or dead, so a
+    /// fast drag leaves no gaps. Parts of the stroke off the board are
+    /// dropped, as are non-finite points and a trailing unpaired
+    /// coordinate. Nothing is painted unless `cell_size` is positive and
+    /// finite.
+    pub fn paint_drag(&mut self, screen_coords: &[f32], cell_size: f32, alive: bool) {
+        if !(cell_size.is_finite() && cell_size > 0.0) || self.cells.is_empty() {
+            return;
         }
+        let cell = if alive { Cell::Alive } else { Cell::Dead };
+        // In cells, as (row, col).
+        let points: Vec<(f64, f64)> = screen_coords
+            .chunks_exact(2)
+            .map(|point| {
+                (
+                    point[1] as f64 / cell_size as f64,
+                    point[0] as f64 / cell_size as f64,
+                )
+            })
+            .filter(|&(row, col)| row.is_finite() && col.is_finite())
+            .collect();
+        let segments: Vec<((f64, f64), (f64, f64))> = match points.len() {
+            1 => vec![(points[0], points[0])],
+            _ => points.windows(2).map(|pair| (pair[0], pair[1])).collect(),
+        };
+
+        let (height, width) = (self.height as f64, self.width as f64);
+        let (last_row, last_col) = (self.height as i64 - 1, self.width as i64 - 1);
+        let on_board = |(row, col): (f64, f64)| {
+            (
+                (row.floor() as i64).clamp(0, last_row),
+                (col.floor() as i64).clamp(0, last_col),
+            )
+        };
+        for (start, end) in segments {
+            let (start, end) = match clip_segment(start, end, height, width) {
+                Some(clipped) => clipped,
+                None => continue,
+            };
+            // Bresenham's line between the clipped ends, which both lie on
+            // the board, so the walk is at most a board's width or height.
+            let ((mut row, mut col), (end_row, end_col)) = (on_board(start), on_board(end));
+            let (d_row, d_col) = (-(end_row - row).abs(), (end_col - col).abs());
+            let (step_row, step_col) = ((end_row - row).signum(), (end_col - col).signum());
+            let mut error = d_col + d_row;
+            loop {
+                let idx = self.get_index(row as u32, col as u32);
+                self.cells[idx] = cell;
+                if (row, col) == (end_row, end_col) {
+                    break;
+                }
+                let doubled = 2 * error;
+                if doubled >= d_row {
+                    error += d_row;
+                    col += step_col;
+                }
+                if doubled <= d_col {
+                    error += d_col;
+                    row += step_row;
+                }
+            }
+        }
+        self.cells_edited();
+    }
+
+    /// What a `current` cell with `neighbors` live neighbors becomes on the
+    /// next tick under the current rule, without touching the board.
+    pub fn next_state(&self, current: Cell, neighbors: u8) -> Cell {
+        self.rule.next(current, neighbors)
     }
 
     pub fn tick(&mut self) {
-        let mut next = self.cells.clone();
+        self.history.record(&self.cells);
+        self.step();
+    }
 
-        for row in 0..self.height {
-            for col in 0..self.width {
-                let idx = self.get_index(row, col);
-                let cell = self.cells[idx];
-                let live_neighbors = self.live_neighbor_count(row, col);
-                let next_cell = match (cell, live_neighbors) {
-                    (Cell::Alive, x) if x < 2 => Cell::Dead,
-                    (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
-                    (Cell::Alive, x) if x > 3 => Cell::Dead,
-                    (Cell::Dead, 3) => Cell::Alive,
-                    (otherwise, _) => otherwise,
-                };
+    /// Ticks at most `max` times, stopping as soon as a tick leaves the
+    /// board unchanged, and returns how many ticks ran. That last tick
+    /// counts, so a still life takes 1.
+    pub fn tick_n_or_stable(&mut self, max: u32) -> u32 {
+        for ticks in 1..=max {
+            self.tick();
+            if self.generations_since_change > 0 {
+                return ticks;
+            }
+        }
+        max
+    }
 
-                next[idx] = next_cell;
+    /// Ticks until the population is exactly `target`, at most `max_steps`
+    /// times, and returns the generation it got there, or `None` if it
+    /// didn't. A board already at `target` doesn't tick at all.
+    pub fn run_until_population(&mut self, target: u32, max_steps: u32) -> Option<u32> {
+        for step in 0..=max_steps {
+            if self.population() == target {
+                return Some(self.generation);
+            }
+            if step < max_steps {
+                self.tick();
             }
         }
+        None
+    }
 
-        self.cells = next;
+    /// Births on each of the next `ticks` generations, computed on a copy so
+    /// this universe is left untouched.
+    pub fn birth_rate_series(&self, ticks: u32) -> Box<[u32]> {
+        let mut copy = self.scratch();
+        (0..ticks).map(|_| copy.step().0).collect()
+    }
+
+    /// Deaths on each of the next `ticks` generations, computed on a copy so
+    /// this universe is left untouched.
+    pub fn death_rate_series(&self, ticks: u32) -> Box<[u32]> {
+        let mut copy = self.scratch();
+        (0..ticks).map(|_| copy.step().1).collect()
+    }
+}
+
+fn check_bitset_len(width: u32, height: u32, words: usize) -> Result<(), Error> {
+    let needed = ((width * height) as usize).div_ceil(64);
+    if words < needed {
+        return Err(Error::new(format!(
+            "a {}x{} grid needs {} words, buffer has {}",
+            width, height, needed, words
+        )));
+    }
+    Ok(())
+}
+
+// The number of cells in a `width` x `height` board, or an error if it
+// overflows or passes `MAX_CELLS`, for sizes read from untrusted input.
+pub(crate) fn checked_cell_count(width: u32, height: u32) -> Result<usize, Error> {
+    width
+        .checked_mul(height)
+        .filter(|&cells| cells <= MAX_CELLS)
+        .map(|cells| cells as usize)
+        .ok_or_else(|| {
+            Error::new(format!(
+                "a {}x{} universe is larger than the {} cell limit",
+                width, height, MAX_CELLS
+            ))
+        })
+}
+
+impl Default for Universe {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -239,13 +923,18 @@ impl fmt::Display for Universe {
                 let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
                 write!(f, "{}", symbol)?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
 impl Cell {
+    /// The cell's byte value in the buffer behind `Universe::cells`.
+    pub fn as_byte(&self) -> u8 {
+        *self as u8
+    }
+
     fn toggle(&mut self) {
         *self = match *self {
             Cell::Dead => Cell::Alive,
@@ -253,3 +942,39 @@ impl Cell {
         };
     }
 }
+
+// The part of the segment from `start` to `end`, as (row, col) points, that
+// lies within `0..=height` x `0..=width`, by Liang-Barsky clipping.
+fn clip_segment(
+    start: (f64, f64),
+    end: (f64, f64),
+    height: f64,
+    width: f64,
+) -> Option<((f64, f64), (f64, f64))> {
+    let (d_row, d_col) = (end.0 - start.0, end.1 - start.1);
+    let (mut enter, mut leave) = (0.0f64, 1.0f64);
+    for (delta, distance) in [
+        (-d_row, start.0),
+        (d_row, height - start.0),
+        (-d_col, start.1),
+        (d_col, width - start.1),
+    ] {
+        if delta == 0.0 {
+            if distance < 0.0 {
+                return None;
+            }
+        } else {
+            let t = distance / delta;
+            if delta < 0.0 {
+                enter = enter.max(t);
+            } else {
+                leave = leave.min(t);
+            }
+        }
+    }
+    if enter > leave {
+        return None;
+    }
+    let at = |t: f64| (start.0 + t * d_row, start.1 + t * d_col);
+    Some((at(enter), at(leave)))
+}
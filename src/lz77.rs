@@ -0,0 +1,80 @@
+//! A small LZ77 compressor, used to estimate how much structure a board
+//! has.
+//!
+//! The output is a sequence of tokens. A byte below 128 starts a run of
+//! that many plus one literal bytes, which follow it. A byte of 128 or more
+//! is a back-reference: it copies `byte - 128 + MIN_MATCH` bytes starting
+//! the big-endian `u16` that follows it bytes back.
+
+use std::collections::HashMap;
+
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 127 + MIN_MATCH;
+const MAX_LITERALS: usize = 128;
+const WINDOW: usize = u16::MAX as usize;
+// Earlier occurrences of a 3-byte prefix tried per position; bounds the
+// cost on long runs of the same byte.
+const MAX_CANDIDATES: usize = 32;
+
+pub(crate) fn compress(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut literals = Vec::new();
+    let mut seen: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let (length, distance) = longest_match(input, pos, &seen);
+        let advance = if length >= MIN_MATCH {
+            flush_literals(&mut output, &mut literals);
+            output.push((128 + length - MIN_MATCH) as u8);
+            output.extend_from_slice(&(distance as u16).to_be_bytes());
+            length
+        } else {
+            literals.push(input[pos]);
+            if literals.len() == MAX_LITERALS {
+                flush_literals(&mut output, &mut literals);
+            }
+            1
+        };
+
+        for start in pos..pos + advance {
+            if let Some(prefix) = input.get(start..start + MIN_MATCH) {
+                seen.entry(prefix).or_default().push(start);
+            }
+        }
+        pos += advance;
+    }
+    flush_literals(&mut output, &mut literals);
+    output
+}
+
+// The (length, distance) of the longest earlier match for the bytes at
+// `pos`, or a length of 0 if there is none.
+fn longest_match(input: &[u8], pos: usize, seen: &HashMap<&[u8], Vec<usize>>) -> (usize, usize) {
+    let candidates = match input.get(pos..pos + MIN_MATCH).and_then(|p| seen.get(p)) {
+        Some(candidates) => candidates,
+        None => return (0, 0),
+    };
+
+    let limit = (input.len() - pos).min(MAX_MATCH);
+    candidates
+        .iter()
+        .rev()
+        .take(MAX_CANDIDATES)
+        .take_while(|&&start| pos - start <= WINDOW)
+        .map(|&start| {
+            let length = (0..limit)
+                .take_while(|&i| input[start + i] == input[pos + i])
+                .count();
+            (length, pos - start)
+        })
+        .max_by_key(|&(length, distance)| (length, std::cmp::Reverse(distance)))
+        .unwrap_or((0, 0))
+}
+
+fn flush_literals(output: &mut Vec<u8>, literals: &mut Vec<u8>) {
+    if !literals.is_empty() {
+        output.push((literals.len() - 1) as u8);
+        output.append(literals);
+    }
+}
@@ -0,0 +1,71 @@
+//! Margolus-neighborhood block automata.
+//!
+//! The grid is partitioned into 2x2 blocks and each block is replaced
+//! wholesale through a 16-entry lookup table. Phase 0 uses blocks whose
+//! top-left corner sits on even coordinates, phase 1 shifts the partition by
+//! (1, 1) and wraps around the edges. A full Margolus step is phase 0
+//! followed by phase 1; dimensions should be even for the partitions to tile.
+//!
+//! Block states are 4-bit numbers: bit 0 is the top-left cell, bit 1
+//! top-right, bit 2 bottom-left and bit 3 bottom-right.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+/// The table that leaves every block as it is.
+pub(crate) const IDENTITY: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+#[wasm_bindgen]
+impl Universe {
+    /// Sets the block lookup table. `table[state]` is the next state of a
+    /// block currently in `state`; it must have 16 entries below 16.
+    pub fn set_margolus_rule(&mut self, table: &[u8]) -> Result<(), Error> {
+        if table.len() != 16 {
+            return Err(Error::new("margolus rule must have 16 entries"));
+        }
+        if table.iter().any(|&next| next > 15) {
+            return Err(Error::new("margolus rule entries must be below 16"));
+        }
+        self.margolus_rule.copy_from_slice(table);
+        Ok(())
+    }
+
+    /// Applies one half-tick of the Margolus partitioning. `phase` 0 updates
+    /// the even blocks, any other value the odd ones.
+    pub fn tick_margolus_phase(&mut self, phase: u8) {
+        let offset = if phase == 0 { 0 } else { 1 };
+        let mut next = self.cells.clone();
+
+        for row in (offset..self.height + offset).step_by(2) {
+            for col in (offset..self.width + offset).step_by(2) {
+                let idx = [
+                    self.get_index(row % self.height, col % self.width),
+                    self.get_index(row % self.height, (col + 1) % self.width),
+                    self.get_index((row + 1) % self.height, col % self.width),
+                    self.get_index((row + 1) % self.height, (col + 1) % self.width),
+                ];
+
+                let state = idx
+                    .iter()
+                    .enumerate()
+                    .fold(0, |state, (bit, &i)| state | (self.cells[i] as u8) << bit);
+                let next_state = self.margolus_rule[state as usize];
+
+                for (bit, &i) in idx.iter().enumerate() {
+                    next[i] = if next_state & (1 << bit) == 0 {
+                        Cell::Dead
+                    } else {
+                        Cell::Alive
+                    };
+                }
+            }
+        }
+
+        let changed = next != self.cells;
+        self.cells = next;
+        if changed {
+            self.cells_edited();
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Free-form key/value notes attached to a universe, such as a pattern's
+//! name or author. They are saved as `#C key=value` lines by `to_rle` and
+//! read back by `from_rle`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Error, Universe};
+
+/// The key that always reads back the current rule.
+pub(crate) const RULE_KEY: &str = "rule";
+
+// Keys are identifiers, so that `#C` lines of free text aren't mistaken for
+// metadata when read back.
+pub(crate) fn is_metadata_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Stores `value` under `key`. Keys are made of ASCII letters, digits,
+    /// `_`, `-` and `.`, and values can't contain line breaks, so every entry
+    /// survives `to_rle`. Setting `rule` switches the universe's rule
+    /// instead, like `set_rule`.
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        if !is_metadata_key(key) {
+            return Err(Error::new(format!("invalid metadata key {:?}", key)));
+        }
+        if value.contains(['\n', '\r']) {
+            return Err(Error::new(format!("invalid metadata value {:?}", value)));
+        }
+
+        if key == RULE_KEY {
+            self.set_rule(value)?;
+        } else {
+            self.metadata.insert(key.to_string(), value.to_string());
+        }
+        Ok(())
+    }
+
+    /// The value stored under `key`. `rule` is always present and gives the
+    /// current rule.
+    pub fn get_metadata(&self, key: &str) -> Option<String> {
+        if key == RULE_KEY {
+            Some(self.rule.to_string())
+        } else {
+            self.metadata.get(key).cloned()
+        }
+    }
+}
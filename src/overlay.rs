@@ -0,0 +1,53 @@
+//! Combining two boards of the same size cell by cell.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+/// How `Universe::overlay` combines a cell from each board.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    Or,
+    And,
+    Xor,
+    /// Alive on `self` and dead on `other`.
+    Subtract,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// A new universe whose cells combine this board's with `other`'s,
+    /// following this board's rule. Both must have the same dimensions.
+    pub fn overlay(&self, other: &Universe, mode: BlendMode) -> Result<Universe, Error> {
+        let mut combined = Universe::empty(self.width, self.height);
+        combined.cells.copy_from_slice(&self.cells);
+        combined.rule = self.rule;
+        combined.apply_overlay(other, mode)?;
+        Ok(combined)
+    }
+
+    /// Like `overlay`, but writes the result into this universe.
+    pub fn apply_overlay(&mut self, other: &Universe, mode: BlendMode) -> Result<(), Error> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(Error::new(format!(
+                "cannot overlay a {}x{} universe on a {}x{} one",
+                other.width, other.height, self.width, self.height
+            )));
+        }
+
+        for (cell, &theirs) in self.cells.iter_mut().zip(&other.cells) {
+            let mine = *cell == Cell::Alive;
+            let theirs = theirs == Cell::Alive;
+            let alive = match mode {
+                BlendMode::Or => mine || theirs,
+                BlendMode::And => mine && theirs,
+                BlendMode::Xor => mine != theirs,
+                BlendMode::Subtract => mine && !theirs,
+            };
+            *cell = if alive { Cell::Alive } else { Cell::Dead };
+        }
+        self.cells_edited();
+        Ok(())
+    }
+}
@@ -0,0 +1,530 @@
+//! Spawners for well-known patterns.
+//!
+//! Each pattern is a table of (row, col) offsets from an anchor cell, placed
+//! with `Universe::add_pattern` so every spawner wraps around the edges the
+//! same way.
+
+use std::collections::HashSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+/// A glider heading down and to the right.
+pub const GLIDER: [(i32, i32); 5] = [(-1, 0), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// The period-3 pulsar, centred on the anchor.
+pub const PULSAR: [(i32, i32); 48] = [
+    (-6, -4),
+    (-6, -3),
+    (-6, -2),
+    (-6, 2),
+    (-6, 3),
+    (-6, 4),
+    (-4, -6),
+    (-4, -1),
+    (-4, 1),
+    (-4, 6),
+    (-3, -6),
+    (-3, -1),
+    (-3, 1),
+    (-3, 6),
+    (-2, -6),
+    (-2, -1),
+    (-2, 1),
+    (-2, 6),
+    (-1, -4),
+    (-1, -3),
+    (-1, -2),
+    (-1, 2),
+    (-1, 3),
+    (-1, 4),
+    (6, -4),
+    (6, -3),
+    (6, -2),
+    (6, 2),
+    (6, 3),
+    (6, 4),
+    (4, -6),
+    (4, -1),
+    (4, 1),
+    (4, 6),
+    (3, -6),
+    (3, -1),
+    (3, 1),
+    (3, 6),
+    (2, -6),
+    (2, -1),
+    (2, 1),
+    (2, 6),
+    (1, -4),
+    (1, -3),
+    (1, -2),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+];
+
+/// Paul Callahan's 10-cell infinite-growth pattern, which settles into a
+/// block-laying switch engine (Charles Corderman's switch engine plus the
+/// debris that feeds it). Offsets are from the centre of its 8x6 box.
+pub const INFINITE_GROWTH: [(i32, i32); 10] = [
+    (-3, 2),
+    (-2, 0),
+    (-2, 2),
+    (-2, 3),
+    (-1, 0),
+    (-1, 2),
+    (0, 0),
+    (1, -2),
+    (2, -4),
+    (2, -2),
+];
+
+/// Charles Corderman's switch engine, the smallest known pattern that
+/// travels while leaving debris behind. On its own it wrecks itself after
+/// a few thousand generations. Offsets are from the centre of its 6x4 box.
+pub const SWITCH_ENGINE: [(i32, i32); 8] = [
+    (-2, -2),
+    (-2, 0),
+    (-1, -3),
+    (0, -2),
+    (0, 1),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+];
+
+/// The Herschel, a heptomino that turns up in many reactions and is the
+/// usual signal carried by conduits. On its own it settles after 128
+/// generations, throwing off two gliders. Offsets are from the top-left
+/// cell of its 3x4 box.
+pub const HERSCHEL: [(i32, i32); 7] = [(0, 0), (1, 0), (1, 1), (1, 2), (2, 0), (2, 2), (3, 2)];
+
+/// Bill Gosper's glider gun, which fires a glider down and to the right
+/// every 30 generations. Offsets are from the top-left cell of its 36x9
+/// box.
+pub const GOSPER_GLIDER_GUN: [(i32, i32); 36] = [
+    (0, 24),
+    (1, 22),
+    (1, 24),
+    (2, 12),
+    (2, 13),
+    (2, 20),
+    (2, 21),
+    (2, 34),
+    (2, 35),
+    (3, 11),
+    (3, 15),
+    (3, 20),
+    (3, 21),
+    (3, 34),
+    (3, 35),
+    (4, 0),
+    (4, 1),
+    (4, 10),
+    (4, 16),
+    (4, 20),
+    (4, 21),
+    (5, 0),
+    (5, 1),
+    (5, 10),
+    (5, 14),
+    (5, 16),
+    (5, 17),
+    (5, 22),
+    (5, 24),
+    (6, 10),
+    (6, 16),
+    (6, 24),
+    (7, 11),
+    (7, 15),
+    (8, 12),
+    (8, 13),
+];
+
+// Spacings at which `add_opposing_gun_pair`'s streams annihilate cleanly,
+// as (separation % 15, column offset of the second gun). Below
+// `MIN_GUN_SEPARATION` the guns are too close for the streams to meet.
+const GUN_PAIR_SPACINGS: [(u32, i32); 5] = [(2, 7), (3, 5), (4, 7), (10, 7), (11, 5)];
+const MIN_GUN_SEPARATION: u32 = 32;
+
+/// The Eater 1 (fishhook), a still life that swallows gliders arriving from
+/// the upper left along its diagonal. Offsets are from the top-left cell of
+/// its 4x4 box.
+pub const EATER_1: [(i32, i32); 7] = [(0, 0), (0, 1), (1, 0), (1, 2), (2, 2), (3, 2), (3, 3)];
+
+/// Bill Gosper's queen bee shuttle, a period-30 oscillator: the queen bee
+/// travels back and forth between two blocks that tidy up after it.
+/// Offsets are from the centre of its 22x7 box.
+pub const QUEEN_BEE_SHUTTLE: [(i32, i32); 20] = [
+    (-3, -2),
+    (-2, -4),
+    (-2, -2),
+    (-1, -5),
+    (-1, -3),
+    (0, -11),
+    (0, -10),
+    (0, -6),
+    (0, -3),
+    (0, 9),
+    (0, 10),
+    (1, -11),
+    (1, -10),
+    (1, -5),
+    (1, -3),
+    (1, 9),
+    (1, 10),
+    (2, -4),
+    (2, -2),
+    (3, -2),
+];
+
+/// The p60 glider shuttle: a glider bouncing between two pentadecathlons,
+/// each of which turns it back the way it came. The pentadecathlons alone
+/// have period 15, but the glider needs 60 generations for a round trip.
+/// Offsets are from the top-left cell of its 35x7 box. See
+/// `add_p60_oscillator` for the room it needs.
+pub const P60_OSCILLATOR: [(i32, i32); 29] = [
+    (0, 2),
+    (0, 7),
+    (1, 0),
+    (1, 1),
+    (1, 3),
+    (1, 4),
+    (1, 5),
+    (1, 6),
+    (1, 8),
+    (1, 9),
+    (2, 2),
+    (2, 7),
+    (3, 16),
+    (4, 17),
+    (4, 18),
+    (4, 27),
+    (4, 32),
+    (5, 16),
+    (5, 17),
+    (5, 25),
+    (5, 26),
+    (5, 28),
+    (5, 29),
+    (5, 30),
+    (5, 31),
+    (5, 33),
+    (5, 34),
+    (6, 27),
+    (6, 32),
+];
+
+/// The tumbler, a period-14 oscillator that flips upside down every 7
+/// generations. Offsets are from the centre of its 9x5 box.
+pub const P14_OSCILLATOR: [(i32, i32); 16] = [
+    (-2, -3),
+    (-2, 3),
+    (-1, -4),
+    (-1, -2),
+    (-1, 2),
+    (-1, 4),
+    (0, -4),
+    (0, -1),
+    (0, 1),
+    (0, 4),
+    (1, -2),
+    (1, 2),
+    (2, -2),
+    (2, -1),
+    (2, 1),
+    (2, 2),
+];
+
+/// The copperhead, a period-10 spaceship that moves one row up every 10
+/// generations. Offsets are from the centre of its 8x12 box.
+pub const COPPERHEAD: [(i32, i32); 28] = [
+    (-6, -3),
+    (-6, -2),
+    (-6, 1),
+    (-6, 2),
+    (-5, -1),
+    (-5, 0),
+    (-4, -1),
+    (-4, 0),
+    (-3, -4),
+    (-3, -2),
+    (-3, 1),
+    (-3, 3),
+    (-2, -4),
+    (-2, 3),
+    (0, -4),
+    (0, 3),
+    (1, -3),
+    (1, -2),
+    (1, 1),
+    (1, 2),
+    (2, -2),
+    (2, -1),
+    (2, 0),
+    (2, 1),
+    (4, -1),
+    (4, 0),
+    (5, -1),
+    (5, 0),
+];
+
+/// The weekender, a period-7 spaceship that moves two rows up every 7
+/// generations (speed 2c/7). Offsets are from the centre of its 16x11 box.
+pub const WEEKENDER: [(i32, i32); 36] = [
+    (-5, -7),
+    (-5, 6),
+    (-4, -7),
+    (-4, 6),
+    (-3, -8),
+    (-3, -6),
+    (-3, 5),
+    (-3, 7),
+    (-2, -7),
+    (-2, 6),
+    (-1, -7),
+    (-1, 6),
+    (0, -6),
+    (0, -2),
+    (0, -1),
+    (0, 0),
+    (0, 1),
+    (0, 5),
+    (1, -2),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (2, -6),
+    (2, -5),
+    (2, -4),
+    (2, -3),
+    (2, 2),
+    (2, 3),
+    (2, 4),
+    (2, 5),
+    (4, -4),
+    (4, 3),
+    (5, -3),
+    (5, -2),
+    (5, 1),
+    (5, 2),
+];
+
+/// The axis along which `make_glider_collider` sends gliders at each other.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    pub fn add_glider(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &GLIDER);
+    }
+
+    pub fn add_pulsar(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &PULSAR);
+    }
+
+    pub fn add_eater_1(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &EATER_1);
+    }
+
+    pub fn add_herschel(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &HERSCHEL);
+    }
+
+    pub fn add_gosper_glider_gun(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &GOSPER_GLIDER_GUN);
+    }
+
+    /// Places two Gosper guns firing at each other, so their gliders meet
+    /// head on and annihilate, leaving a period-30 pattern. The first gun's
+    /// box has its top-left corner at (`row`, `col`). The second is turned
+    /// half around, with the bottom-right corner of its box `separation`
+    /// rows below and a few more columns to the right.
+    ///
+    /// The streams only annihilate cleanly at certain spacings, at most six
+    /// apart, so `separation` is rounded down to the nearest one that works.
+    /// It must be at least 32. The pair covers about `separation + 1` rows by
+    /// `separation + 8` columns, and the board needs a few cells of margin
+    /// around it.
+    pub fn add_opposing_gun_pair(
+        &mut self,
+        row: i32,
+        col: i32,
+        separation: u32,
+    ) -> Result<(), Error> {
+        if separation < MIN_GUN_SEPARATION {
+            return Err(Error::new(format!(
+                "guns need a separation of at least {}, not {}",
+                MIN_GUN_SEPARATION, separation
+            )));
+        }
+        let (separation, col_offset) = (MIN_GUN_SEPARATION..=separation)
+            .rev()
+            .find_map(|s| {
+                GUN_PAIR_SPACINGS
+                    .iter()
+                    .find(|&&(residue, _)| s % 15 == residue)
+                    .map(|&(_, col_offset)| (s, col_offset))
+            })
+            .unwrap();
+
+        self.add_gosper_glider_gun(row, col);
+        if self.cells.is_empty() {
+            return Ok(());
+        }
+        // The second gun's corner can lie past the range of an `i32` anchor,
+        // so it is placed cell by cell, wrapping like `add_pattern`.
+        let turned_row = row as i64 + separation as i64;
+        let turned_col = col as i64 + separation as i64 + col_offset as i64;
+        for &(delta_row, delta_col) in &GOSPER_GLIDER_GUN {
+            let idx =
+                self.wrapped_index(turned_row - delta_row as i64, turned_col - delta_col as i64);
+            self.cells[idx] = Cell::Alive;
+        }
+        self.cells_edited();
+        Ok(())
+    }
+
+    pub fn add_queen_bee_shuttle(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &QUEEN_BEE_SHUTTLE);
+    }
+
+    pub fn add_copperhead(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &COPPERHEAD);
+    }
+
+    pub fn add_weekender(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &WEEKENDER);
+    }
+
+    /// Places `P14_OSCILLATOR`. While it runs it reaches from 2 rows above
+    /// to 4 rows below the anchor and 4 columns either side of it, so the
+    /// board must be at least 10x8 for it to keep its period.
+    pub fn add_p14_oscillator(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &P14_OSCILLATOR);
+    }
+
+    /// Places `P60_OSCILLATOR`. While it runs it reaches from 3 rows above to
+    /// 9 rows below the anchor and from 3 columns left to 37 columns right
+    /// of it, so it needs a board of at least 41x13 to run without wrapping
+    /// around into itself.
+    pub fn add_p60_oscillator(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &P60_OSCILLATOR);
+    }
+
+    /// Places the 10-cell infinite-growth pattern. On a torus nothing grows
+    /// forever: gliders thrown off while the engine forms eventually wrap
+    /// around and wreck it. On a 256x256 board that takes about 1,100
+    /// generations; use at least 128x128 and the largest board you can.
+    pub fn add_infinite_growth(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &INFINITE_GROWTH);
+    }
+
+    /// Places the 8-cell switch engine. It drifts diagonally, throwing
+    /// gliders and debris that wrap around a torus and collide with it; on a
+    /// board of at least 128x128 its first 1,000 generations match those it
+    /// would have on an unbounded plane.
+    pub fn add_switch_engine(&mut self, row: i32, col: i32) {
+        self.add_pattern(row, col, &SWITCH_ENGINE);
+    }
+
+    /// Places `n` pairs of gliders that converge along `approach_axis` and
+    /// collide near the centre of that axis. Pairs are spread evenly along
+    /// the other axis; both gliders of a pair drift the same way along it, so
+    /// they stay level until they meet.
+    pub fn make_glider_collider(&mut self, n: u32, approach_axis: Axis) {
+        let (along, across) = match approach_axis {
+            Axis::Horizontal => (self.width, self.height),
+            Axis::Vertical => (self.height, self.width),
+        };
+        let center = (along / 2) as i32;
+        let gap = (along / 4) as i32;
+
+        for pair in 0..n {
+            let lane = ((2 * pair as u64 + 1) * across as u64 / (2 * n as u64)) as i32;
+            match approach_axis {
+                Axis::Horizontal => {
+                    self.add_glider_heading(lane, center - gap, true, true);
+                    self.add_glider_heading(lane, center + gap, true, false);
+                }
+                Axis::Vertical => {
+                    self.add_glider_heading(center - gap, lane, true, true);
+                    self.add_glider_heading(center + gap, lane, false, true);
+                }
+            }
+        }
+    }
+}
+
+impl Universe {
+    /// Whether the pattern `offsets` appears anywhere on the board, wrapping
+    /// around the edges: at some position every cell of the pattern is alive
+    /// and every other cell within one cell of its bounding box is dead. So
+    /// a glider counts as found only while it is on its own, not when it is
+    /// part of a larger blob. An empty pattern is never found.
+    pub fn contains_pattern(&self, offsets: &[(i32, i32)]) -> bool {
+        (0..self.height as i32)
+            .any(|row| (0..self.width as i32).any(|col| self.pattern_at(row, col, offsets)))
+    }
+
+    /// Like `contains_pattern`, but also finds the pattern rotated or
+    /// reflected, in any of its eight orientations.
+    pub fn contains_pattern_in_any_orientation(&self, offsets: &[(i32, i32)]) -> bool {
+        (0..8).any(|orientation| {
+            let oriented: Vec<(i32, i32)> = offsets
+                .iter()
+                .map(|&(delta_row, delta_col)| {
+                    let (delta_row, delta_col) = if orientation >= 4 {
+                        (delta_col, delta_row)
+                    } else {
+                        (delta_row, delta_col)
+                    };
+                    let row_sign = if orientation & 1 == 1 { -1 } else { 1 };
+                    let col_sign = if orientation & 2 == 2 { -1 } else { 1 };
+                    (delta_row * row_sign, delta_col * col_sign)
+                })
+                .collect();
+            self.contains_pattern(&oriented)
+        })
+    }
+
+    fn pattern_at(&self, row: i32, col: i32, offsets: &[(i32, i32)]) -> bool {
+        if offsets.is_empty() {
+            return false;
+        }
+        let index = |delta_row: i32, delta_col: i32| {
+            self.wrapped_index(row as i64 + delta_row as i64, col as i64 + delta_col as i64)
+        };
+        let pattern: HashSet<usize> = offsets.iter().map(|&(r, c)| index(r, c)).collect();
+        if pattern.iter().any(|&idx| self.cells[idx] == Cell::Dead) {
+            return false;
+        }
+
+        let rows = offsets.iter().map(|&(r, _)| r);
+        let cols = offsets.iter().map(|&(_, c)| c);
+        let (top, bottom) = (rows.clone().min().unwrap(), rows.max().unwrap());
+        let (left, right) = (cols.clone().min().unwrap(), cols.max().unwrap());
+        (top - 1..=bottom + 1).all(|delta_row| {
+            (left - 1..=right + 1).all(|delta_col| {
+                let idx = index(delta_row, delta_col);
+                pattern.contains(&idx) || self.cells[idx] == Cell::Dead
+            })
+        })
+    }
+
+    // A glider reflected from `GLIDER` so it travels down or up and right or
+    // left.
+    fn add_glider_heading(&mut self, row: i32, col: i32, down: bool, right: bool) {
+        let row_sign = if down { 1 } else { -1 };
+        let col_sign = if right { 1 } else { -1 };
+        let offsets =
+            GLIDER.map(|(delta_row, delta_col)| (delta_row * row_sign, delta_col * col_sign));
+        self.add_pattern(row, col, &offsets);
+    }
+}
@@ -0,0 +1,51 @@
+//! Timing helpers for finding where a tick spends its time.
+
+use std::hint::black_box;
+use wasm_bindgen::prelude::*;
+
+use crate::{utils, Universe};
+
+const PROFILE_RUNS: u32 = 100;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Average time in microseconds to compute the next state of each row,
+    /// measured over 100 runs per row against the current, un-advanced
+    /// board. The result has one entry per row; dense rows stand out as the
+    /// expensive ones. The board itself is not changed.
+    pub fn profile_tick_hotspots(&self) -> Box<[f64]> {
+        (0..self.height)
+            .map(|row| {
+                let start = utils::now_ms();
+                for _ in 0..PROFILE_RUNS {
+                    for col in 0..self.width {
+                        let cell = self.cells[self.get_index(row, col)];
+                        let live_neighbors = self.live_neighbor_count(row, col);
+                        black_box(self.next_state(cell, live_neighbors));
+                    }
+                }
+                let elapsed = utils::now_ms() - start;
+                (elapsed * 1000.0 / PROFILE_RUNS as f64).max(0.0)
+            })
+            .collect()
+    }
+
+    /// Ticks as many times as fit in `duration_ms` and returns the
+    /// throughput in cells per second, `ticks * width * height` over the
+    /// time taken. At least one tick runs, and ticking goes on until the
+    /// clock has visibly moved, so the result is always finite. The board
+    /// is left wherever the ticks took it.
+    pub fn benchmark(&mut self, duration_ms: f64) -> f64 {
+        let start = utils::now_ms();
+        let mut ticks = 0u64;
+        let elapsed = loop {
+            self.tick();
+            ticks += 1;
+            let elapsed = utils::now_ms() - start;
+            if elapsed >= duration_ms && elapsed > 0.0 {
+                break elapsed;
+            }
+        };
+        ticks as f64 * self.cells.len() as f64 / (elapsed / 1000.0)
+    }
+}
@@ -0,0 +1,178 @@
+//! Seeding a universe with a QR-code-like picture of some text.
+//!
+//! The matrix follows the QR layout closely enough to look right: finder
+//! squares in three corners, timing lines, byte-mode data laid out in the
+//! usual two-column zigzag and a checkerboard mask. There is no error
+//! correction or format information, so scanners will not read it.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+// Dead cells around the matrix, as a real QR code's quiet zone.
+const QUIET_ZONE: u32 = 4;
+// Largest QR version, 177 modules a side.
+const MAX_VERSION: u32 = 40;
+// Byte mode length field is 8 bits wide.
+const MAX_BYTES: usize = 255;
+
+#[wasm_bindgen]
+impl Universe {
+    /// A square universe holding `data` as a QR-like matrix, centred within
+    /// a quiet zone. The side is at least `min_width`, which fails if that
+    /// makes more than `MAX_CELLS` cells. The same input always produces
+    /// the same board.
+    pub fn from_qr_code(data: &str, min_width: u32) -> Result<Universe, Error> {
+        let bytes = data.as_bytes();
+        if bytes.len() > MAX_BYTES {
+            return Err(Error::new(format!(
+                "QR data is {} bytes; at most {} fit",
+                bytes.len(),
+                MAX_BYTES
+            )));
+        }
+
+        let matrix = Matrix::encode(bytes)?;
+        let side = (matrix.size + 2 * QUIET_ZONE).max(min_width);
+        crate::checked_cell_count(side, side)?;
+        let offset = (side - matrix.size) / 2;
+
+        let mut universe = Universe::empty(side, side);
+        for row in 0..matrix.size {
+            for col in 0..matrix.size {
+                if matrix.dark(row, col) {
+                    let idx = universe.get_index(row + offset, col + offset);
+                    universe.cells[idx] = Cell::Alive;
+                }
+            }
+        }
+        Ok(universe)
+    }
+}
+
+struct Matrix {
+    size: u32,
+    modules: Vec<bool>,
+    // Finder and timing modules, which data is laid out around.
+    reserved: Vec<bool>,
+}
+
+impl Matrix {
+    // The smallest version whose data area holds `bytes`.
+    fn encode(bytes: &[u8]) -> Result<Matrix, Error> {
+        let mut bits = Vec::new();
+        push_bits(&mut bits, 0b0100, 4);
+        push_bits(&mut bits, bytes.len() as u32, 8);
+        for &byte in bytes {
+            push_bits(&mut bits, byte as u32, 8);
+        }
+
+        for version in 1..=MAX_VERSION {
+            let mut matrix = Matrix::with_function_patterns(17 + 4 * version);
+            let capacity = matrix
+                .reserved
+                .iter()
+                .filter(|&&reserved| !reserved)
+                .count();
+            if capacity >= bits.len() {
+                matrix.place_data(&padded(bits, capacity));
+                return Ok(matrix);
+            }
+        }
+        Err(Error::new("QR data does not fit in the largest matrix"))
+    }
+
+    fn with_function_patterns(size: u32) -> Matrix {
+        let cells = (size * size) as usize;
+        let mut matrix = Matrix {
+            size,
+            modules: vec![false; cells],
+            reserved: vec![false; cells],
+        };
+
+        // Each finder sits in an 8x8 corner region including its light
+        // separator.
+        for &(top, left) in &[(0, 0), (0, size - 8), (size - 8, 0)] {
+            for row in top..top + 8 {
+                for col in left..left + 8 {
+                    let idx = matrix.index(row, col);
+                    matrix.reserved[idx] = true;
+                }
+            }
+            let center_row = if top == 0 { 3 } else { size - 4 };
+            let center_col = if left == 0 { 3 } else { size - 4 };
+            for row in center_row - 3..=center_row + 3 {
+                for col in center_col - 3..=center_col + 3 {
+                    let ring = row.abs_diff(center_row).max(col.abs_diff(center_col));
+                    let idx = matrix.index(row, col);
+                    matrix.modules[idx] = ring != 2;
+                }
+            }
+        }
+
+        for i in 8..size - 8 {
+            for idx in [matrix.index(6, i), matrix.index(i, 6)] {
+                matrix.reserved[idx] = true;
+                matrix.modules[idx] = i.is_multiple_of(2);
+            }
+        }
+        matrix
+    }
+
+    // Fills the unreserved modules two columns at a time from the right,
+    // alternately upwards and downwards, skipping the vertical timing line.
+    fn place_data(&mut self, bits: &[bool]) {
+        let mut bits = bits.iter();
+        let mut upwards = true;
+        let mut right = self.size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            for step in 0..self.size {
+                let row = if upwards { self.size - 1 - step } else { step };
+                for col in [right, right - 1] {
+                    let idx = self.index(row, col);
+                    if !self.reserved[idx] {
+                        let bit = *bits.next().unwrap_or(&false);
+                        self.modules[idx] = bit != (row + col).is_multiple_of(2);
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+            upwards = !upwards;
+        }
+    }
+
+    fn index(&self, row: u32, col: u32) -> usize {
+        (row * self.size + col) as usize
+    }
+
+    fn dark(&self, row: u32, col: u32) -> bool {
+        self.modules[self.index(row, col)]
+    }
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: u32, count: u32) {
+    bits.extend((0..count).rev().map(|bit| value >> bit & 1 == 1));
+}
+
+// Ends the message with a terminator and the standard alternating pad
+// bytes, out to `capacity` bits.
+fn padded(mut bits: Vec<bool>, capacity: usize) -> Vec<bool> {
+    let terminator = (capacity - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    while !bits.len().is_multiple_of(8) && bits.len() < capacity {
+        bits.push(false);
+    }
+    for &pad in [0xec, 0x11].iter().cycle() {
+        if bits.len() + 8 > capacity {
+            break;
+        }
+        push_bits(&mut bits, pad, 8);
+    }
+    bits
+}
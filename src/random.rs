@@ -0,0 +1,204 @@
+//! Reproducible random boards.
+//!
+//! `reset` uses `Math.random` and can't be repeated; the seeded variants
+//! here draw from SplitMix64 so the same seed always gives the same board,
+//! on every platform. `reset_soup` follows apgsearch instead, so soups can
+//! be compared with its results.
+
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+
+use crate::{utils, Cell, Error, Universe};
+
+/// SplitMix64, small and fast with good enough statistics for seeding
+/// boards.
+#[derive(Clone, Debug)]
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> SplitMix64 {
+        SplitMix64(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1), from the top 53 bits.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// A 64x64 universe seeded like `reset_with_seed`.
+    pub fn new_with_seed(seed: u64) -> Universe {
+        utils::set_panic_hook();
+
+        let mut universe = Universe::empty(64, 64);
+        universe.reset_with_seed(seed);
+        universe
+    }
+
+    /// Like `reset`, but the board is determined by `seed`.
+    pub fn reset_with_seed(&mut self, seed: u64) {
+        let mut rng = SplitMix64::new(seed);
+        for cell in &mut self.cells {
+            *cell = if rng.next_u64() >> 63 == 1 {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            };
+        }
+        self.seed = Some(seed);
+        self.cells_edited();
+    }
+
+    /// Clears the board and fills the centred `size` x `size` square from a
+    /// SHA-256 stream, the way apgsearch's `hashsoup` builds its 16x16 soups:
+    ///
+    /// 1. The stream is `SHA-256(hash)` (of the UTF-8 bytes of `hash`), then
+    ///    `SHA-256` of that digest, and so on, 32 bytes at a time.
+    /// 2. Bits are read from each byte in turn, most significant first.
+    /// 3. They fill the square row by row, left to right; a set bit is a
+    ///    live cell.
+    ///
+    /// With `size` 16 one digest fills the square, byte `j` giving row
+    /// `j / 2`, exactly as apgsearch does for seed string `hash`.
+    pub fn reset_soup(&mut self, hash: &str, size: u32) -> Result<(), Error> {
+        if size > self.width || size > self.height {
+            return Err(Error::new(format!(
+                "a {}x{} soup does not fit in a {}x{} universe",
+                size, size, self.width, self.height
+            )));
+        }
+
+        let mut digest = Sha256::digest(hash.as_bytes());
+        let mut bits = Vec::with_capacity((size * size) as usize);
+        while bits.len() < (size * size) as usize {
+            bits.extend(
+                digest
+                    .iter()
+                    .flat_map(|&byte| (0..8).rev().map(move |bit| byte >> bit & 1 == 1)),
+            );
+            digest = Sha256::digest(digest);
+        }
+
+        self.cells.iter_mut().for_each(|cell| *cell = Cell::Dead);
+        let top = (self.height - size) / 2;
+        let left = (self.width - size) / 2;
+        for (i, &alive) in bits.iter().take((size * size) as usize).enumerate() {
+            if alive {
+                let idx = self.get_index(top + i as u32 / size, left + i as u32 % size);
+                self.cells[idx] = Cell::Alive;
+            }
+        }
+        self.seed = None;
+        self.cells_edited();
+        Ok(())
+    }
+
+    /// Fills the board with vertical stripes for studying edge effects. The
+    /// columns are split left to right into stripes with widths drawn
+    /// uniformly from `min_col_width..=max_col_width` (the last one cut off
+    /// at the edge), and every stripe is filled on its own with cells alive
+    /// with probability `density`. Returns the stripe widths in order. Draws
+    /// from `Math.random`.
+    pub fn randomize_striped(
+        &mut self,
+        min_col_width: u32,
+        max_col_width: u32,
+        density: f64,
+    ) -> Result<Box<[u32]>, Error> {
+        let widths =
+            self.fill_striped(min_col_width, max_col_width, density, js_sys::Math::random)?;
+        self.seed = None;
+        Ok(widths)
+    }
+
+    /// Like `randomize_striped`, but the board is determined by `seed`.
+    pub fn randomize_striped_with_seed(
+        &mut self,
+        min_col_width: u32,
+        max_col_width: u32,
+        density: f64,
+        seed: u64,
+    ) -> Result<Box<[u32]>, Error> {
+        let mut rng = SplitMix64::new(seed);
+        let widths = self.fill_striped(min_col_width, max_col_width, density, || rng.next_f64())?;
+        self.seed = None;
+        Ok(widths)
+    }
+
+    /// Flips each cell with probability `rate`, drawn from `seed`, to inject
+    /// reproducible noise into a running board. A `rate` of 0 or less
+    /// changes nothing and one of 1 or more flips every cell.
+    pub fn mutate(&mut self, rate: f64, seed: u64) {
+        let mut rng = SplitMix64::new(seed);
+        for cell in &mut self.cells {
+            if rng.next_f64() < rate {
+                cell.toggle();
+            }
+        }
+        self.cells_edited();
+    }
+
+    /// The seed of the last `reset_with_seed` or `new_with_seed`, for
+    /// sharing a board. `None` if the board was never seeded or has since
+    /// been `reset` from `Math.random`.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
+
+impl Universe {
+    // `randomize_striped` with `random` giving numbers in [0, 1).
+    fn fill_striped(
+        &mut self,
+        min_col_width: u32,
+        max_col_width: u32,
+        density: f64,
+        mut random: impl FnMut() -> f64,
+    ) -> Result<Box<[u32]>, Error> {
+        if min_col_width == 0 || min_col_width > max_col_width {
+            return Err(Error::new(format!(
+                "stripe widths {}..={} are not a range of positive widths",
+                min_col_width, max_col_width
+            )));
+        }
+        if !(0.0..=1.0).contains(&density) {
+            return Err(Error::new(format!(
+                "density {} is not between 0 and 1",
+                density
+            )));
+        }
+
+        let mut widths = Vec::new();
+        let mut col = 0;
+        while col < self.width {
+            let spread = (max_col_width - min_col_width + 1) as f64;
+            let width = (min_col_width
+                + ((random() * spread) as u32).min(max_col_width - min_col_width))
+            .min(self.width - col);
+            for row in 0..self.height {
+                for stripe_col in col..col + width {
+                    let idx = self.get_index(row, stripe_col);
+                    self.cells[idx] = if random() < density {
+                        Cell::Alive
+                    } else {
+                        Cell::Dead
+                    };
+                }
+            }
+            widths.push(width);
+            col += width;
+        }
+        self.cells_edited();
+        Ok(widths.into_boxed_slice())
+    }
+}
@@ -0,0 +1,249 @@
+//! Reading and writing patterns in the run-length encoded format used by
+//! most Life software.
+//!
+//! The parser is deliberately forgiving about the quirks found in files
+//! from different tools: upper-case `B`/`O` tags, `<n>$` row skips that leave
+//! blank rows, whitespace and line breaks anywhere in the body, the `!` on
+//! a line of its own, and anything after the `!`. `#C key=value` comment
+//! lines whose key is an identifier carry the universe's metadata; other
+//! comments are skipped, and so is a `rule` key, since the header gives the
+//! rule.
+
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+use crate::rules::Rule;
+use crate::{metadata, Cell, Error, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Parses an RLE pattern into a universe sized by its `x = .., y = ..`
+    /// header and following its `rule`, if it names one. A header asking
+    /// for more than `MAX_CELLS` cells is rejected before anything is
+    /// allocated.
+    pub fn from_rle(rle: &str) -> Result<Universe, Error> {
+        let metadata = rle.lines().filter_map(|line| {
+            let (key, value) = line.trim().strip_prefix("#C")?.split_once('=')?;
+            let key = key.trim();
+            (metadata::is_metadata_key(key) && key != metadata::RULE_KEY).then_some((key, value))
+        });
+        let mut lines = rle
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let header = lines
+            .next()
+            .ok_or_else(|| Error::new("RLE has no header line"))?;
+        let (width, height, rule) = parse_header(header)?;
+        crate::checked_cell_count(width, height)?;
+
+        let mut universe = Universe::empty(width, height);
+        universe.rule = rule;
+        for (key, value) in metadata {
+            universe.metadata.insert(key.to_string(), value.to_string());
+        }
+        let (mut row, mut col) = (0u32, 0u32);
+        let mut count: Option<u32> = None;
+
+        'body: for line in lines {
+            for c in line.chars() {
+                match c {
+                    '0'..='9' => {
+                        let digit = c.to_digit(10).unwrap();
+                        count = Some(
+                            count
+                                .unwrap_or(0)
+                                .checked_mul(10)
+                                .and_then(|n| n.checked_add(digit))
+                                .ok_or_else(|| Error::new("RLE run count is too large"))?,
+                        );
+                        continue;
+                    }
+                    'b' | 'B' | '.' => {
+                        col = col
+                            .checked_add(count.unwrap_or(1))
+                            .ok_or_else(run_out_of_bounds)?
+                    }
+                    'o' | 'O' => {
+                        let run = count.unwrap_or(1);
+                        let end = col.checked_add(run).ok_or_else(run_out_of_bounds)?;
+                        if row >= height || end > width {
+                            return Err(Error::new(format!(
+                                "RLE pattern exceeds its {}x{} header",
+                                width, height
+                            )));
+                        }
+                        for _ in 0..run {
+                            let idx = universe.get_index(row, col);
+                            universe.cells[idx] = Cell::Alive;
+                            col += 1;
+                        }
+                    }
+                    '$' => {
+                        row = row
+                            .checked_add(count.unwrap_or(1))
+                            .ok_or_else(run_out_of_bounds)?;
+                        col = 0;
+                    }
+                    '!' => break 'body,
+                    c if c.is_whitespace() => {}
+                    c => {
+                        return Err(Error::new(format!("unexpected {:?} in RLE body", c)));
+                    }
+                }
+                count = None;
+            }
+        }
+
+        universe.cells_edited();
+        Ok(universe)
+    }
+
+    /// The whole board as RLE, metadata first as `#C key=value` lines in key
+    /// order. Each row stops at its last live cell and trailing blank rows
+    /// are left out, since the format implies them; the header keeps the
+    /// full size so the board round-trips.
+    pub fn to_rle(&self) -> String {
+        let mut rle = String::new();
+        let mut keys: Vec<&String> = self.metadata.keys().collect();
+        keys.sort();
+        for key in keys {
+            writeln!(rle, "#C {}={}", key, self.metadata[key]).unwrap();
+        }
+        writeln!(
+            rle,
+            "x = {}, y = {}, rule = {}",
+            self.width, self.height, self.rule
+        )
+        .unwrap();
+
+        let mut body = RleWriter::default();
+        let mut blank_rows = 0;
+        for line in self.cells.chunks(self.width.max(1) as usize) {
+            let live_len = line
+                .iter()
+                .rposition(|&cell| cell == Cell::Alive)
+                .map_or(0, |last| last + 1);
+            if live_len == 0 {
+                blank_rows += 1;
+                continue;
+            }
+            // Each `$` ends a row, so blank rows before the first live one
+            // need one each, and a gap between two rows one more than its
+            // height.
+            if !body.is_empty() {
+                body.push(blank_rows + 1, '$');
+            } else if blank_rows > 0 {
+                body.push(blank_rows, '$');
+            }
+            blank_rows = 0;
+
+            for run in line[..live_len].chunk_by(|a, b| a == b) {
+                let tag = if run[0] == Cell::Alive { 'o' } else { 'b' };
+                body.push(run.len() as u32, tag);
+            }
+        }
+        body.push(1, '!');
+        rle + &body.finish()
+    }
+}
+
+impl Universe {
+    /// Parses a file holding several RLE patterns, one universe each. Every
+    /// `x = ` header line starts a new pattern, together with the comment
+    /// lines just above it. An error names the pattern, counting from 1.
+    pub fn from_rle_multi(rle: &str) -> Result<Vec<Universe>, Error> {
+        let lines: Vec<&str> = rle.lines().collect();
+        let is_header = |line: &str| {
+            line.trim()
+                .strip_prefix('x')
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        };
+        let headers: Vec<usize> = (0..lines.len()).filter(|&i| is_header(lines[i])).collect();
+        if headers.is_empty() {
+            return Err(Error::new("RLE has no header line"));
+        }
+
+        // Each pattern after the first starts at the comments above its
+        // header, which don't reach back past the previous header.
+        let mut starts = vec![0];
+        for pair in headers.windows(2) {
+            let mut start = pair[1];
+            while start > pair[0] + 1 && lines[start - 1].trim_start().starts_with('#') {
+                start -= 1;
+            }
+            starts.push(start);
+        }
+        starts.push(lines.len());
+
+        starts
+            .windows(2)
+            .enumerate()
+            .map(|(n, section)| {
+                Universe::from_rle(&lines[section[0]..section[1]].join("\n"))
+                    .map_err(|err| Error::new(format!("pattern {}: {}", n + 1, err)))
+            })
+            .collect()
+    }
+}
+
+fn run_out_of_bounds() -> Error {
+    Error::new("RLE run exceeds pattern bounds")
+}
+
+// Lines of RLE body text wrapped at 70 columns, as the format recommends.
+#[derive(Default)]
+struct RleWriter {
+    lines: Vec<String>,
+    line: String,
+}
+
+impl RleWriter {
+    fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.line.is_empty()
+    }
+
+    fn push(&mut self, count: u32, tag: char) {
+        let token = if count == 1 {
+            tag.to_string()
+        } else {
+            format!("{}{}", count, tag)
+        };
+        if self.line.len() + token.len() > 70 {
+            self.lines.push(std::mem::take(&mut self.line));
+        }
+        self.line.push_str(&token);
+    }
+
+    fn finish(mut self) -> String {
+        self.lines.push(self.line);
+        self.lines.join("\n") + "\n"
+    }
+}
+
+// Reads `x = <width>, y = <height>[, rule = <rule>]`.
+fn parse_header(header: &str) -> Result<(u32, u32, Rule), Error> {
+    let (mut width, mut height, mut rule) = (None, None, Rule::default());
+    for field in header.split(',') {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| Error::new(format!("malformed RLE header {:?}", header)))?;
+        let value = value.trim();
+        let dimension = || {
+            value
+                .parse::<u32>()
+                .map_err(|_| Error::new(format!("bad RLE dimension {:?}", value)))
+        };
+        match key.trim() {
+            "x" => width = Some(dimension()?),
+            "y" => height = Some(dimension()?),
+            "rule" => rule = Rule::parse(value)?,
+            _ => {}
+        }
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height, rule)),
+        _ => Err(Error::new(format!("RLE header {:?} lacks x or y", header))),
+    }
+}
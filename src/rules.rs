@@ -0,0 +1,208 @@
+//! Ticking with rules other than Conway's B3/S23.
+//!
+//! A universe carries a life-like rule in birth/survival notation, which
+//! `tick` and everything built on it follow. Rules are accepted as
+//! `B3/S23` (either case) or in the older survival-first `23/3` form, and
+//! always written back as `B3/S23`. Rules with `B0` are rejected: they
+//! bring empty space to life, which the tick's empty-board shortcut and
+//! the torus can't represent sensibly.
+
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Rule {
+    // Bit `n` is set when `n` live neighbours cause a birth or survival.
+    birth: u16,
+    survival: u16,
+}
+
+impl Rule {
+    pub(crate) const CONWAY: Rule = Rule {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+    };
+
+    pub(crate) fn parse(rule: &str) -> Result<Rule, Error> {
+        let invalid = || Error::new(format!("invalid rule {:?}", rule));
+        let upper = rule.trim().to_ascii_uppercase();
+        let (birth, survival) = upper.split_once('/').ok_or_else(invalid)?;
+        let (birth, survival) = match (birth.strip_prefix('B'), survival.strip_prefix('S')) {
+            (Some(birth), Some(survival)) => (birth, survival),
+            // The survival-first form, `23/3`.
+            (None, None) => (survival, birth),
+            _ => return Err(invalid()),
+        };
+
+        let counts = |digits: &str| {
+            digits
+                .chars()
+                .try_fold(0u16, |mask, c| match c.to_digit(10) {
+                    Some(n) if n <= 8 => Ok(mask | 1 << n),
+                    _ => Err(invalid()),
+                })
+        };
+        let parsed = Rule {
+            birth: counts(birth)?,
+            survival: counts(survival)?,
+        };
+        if parsed.birth & 1 != 0 {
+            return Err(Error::new(format!(
+                "B0 rules like {:?} are not supported",
+                rule
+            )));
+        }
+        Ok(parsed)
+    }
+
+    pub(crate) fn next(&self, current: Cell, neighbors: u8) -> Cell {
+        let mask = match current {
+            Cell::Alive => self.survival,
+            Cell::Dead => self.birth,
+        };
+        if mask >> neighbors & 1 == 1 {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::CONWAY
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = |mask: u16| -> String {
+            (0..=8)
+                .filter(|n| mask >> n & 1 == 1)
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survival))
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The current rule, e.g. `B3/S23`.
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    /// Switches to another life-like rule, such as `B36/S23` (HighLife).
+    /// Spellings of the same rule, like `b3/s32` and `23/3`, are equivalent,
+    /// and `rule` reads back the canonical `B3/S23` form.
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), Error> {
+        self.rule = Rule::parse(rule)?;
+        self.history.drop_keyframes();
+        Ok(())
+    }
+
+    /// Whether the current rule is Conway's B3/S23.
+    pub fn is_conway(&self) -> bool {
+        self.rule == Rule::CONWAY
+    }
+
+    /// Switches to `rule`, remembering the current one for `pop_rule`.
+    pub fn push_rule(&mut self, rule: &str) -> Result<(), Error> {
+        let rule = Rule::parse(rule)?;
+        self.rule_stack
+            .push(std::mem::replace(&mut self.rule, rule));
+        self.history.drop_keyframes();
+        Ok(())
+    }
+
+    /// Goes back to the rule in use before the last `push_rule`. Returns
+    /// `false` if no rule was pushed.
+    pub fn pop_rule(&mut self) -> bool {
+        match self.rule_stack.pop() {
+            Some(rule) => {
+                self.rule = rule;
+                self.history.drop_keyframes();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Ticks once with a rule written in JavaScript. `birth_fn(n)` says
+    /// whether a dead cell with `n` live neighbours comes to life and
+    /// `survival_fn(n)` whether a live one stays alive; any truthy return
+    /// counts as yes.
+    ///
+    /// Each function is called once for every neighbour count from 0 to 8
+    /// and the answers are reused for the whole board, so the functions
+    /// must be pure. `birth_fn(0)` must be false.
+    pub fn tick_with_js_rule(
+        &mut self,
+        birth_fn: &js_sys::Function,
+        survival_fn: &js_sys::Function,
+    ) -> Result<(), Error> {
+        let born = js_rule_table(birth_fn, "birth_fn")?;
+        let survives = js_rule_table(survival_fn, "survival_fn")?;
+        if born[0] {
+            return Err(Error::new("birth_fn must be false for 0 neighbours"));
+        }
+
+        self.history.record(&self.cells);
+        self.history.drop_keyframes();
+        self.step_with(|_, cell, neighbors| {
+            let alive = match cell {
+                Cell::Alive => survives[neighbors as usize],
+                Cell::Dead => born[neighbors as usize],
+            };
+            if alive {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            }
+        });
+        Ok(())
+    }
+
+    /// Ticks once with the rule "born with exactly `birth_count` neighbours,
+    /// survives with `survival_min` to `survival_max`", leaving the current
+    /// rule in place. `tick_custom_simple(3, 2, 3)` is an ordinary Conway
+    /// tick. `birth_count` can't be 0, as with `set_rule`.
+    pub fn tick_custom_simple(
+        &mut self,
+        birth_count: u8,
+        survival_min: u8,
+        survival_max: u8,
+    ) -> Result<(), Error> {
+        if birth_count == 0 {
+            return Err(Error::new("births with 0 neighbours are not supported"));
+        }
+        let rule = Rule {
+            birth: if birth_count <= 8 {
+                1 << birth_count
+            } else {
+                0
+            },
+            survival: (survival_min..=survival_max.min(8)).fold(0, |mask, n| mask | 1 << n),
+        };
+
+        self.history.record(&self.cells);
+        self.history.drop_keyframes();
+        self.step_with(|_, cell, neighbors| rule.next(cell, neighbors));
+        Ok(())
+    }
+}
+
+// `f(n)` for every neighbour count `n`.
+fn js_rule_table(f: &js_sys::Function, name: &str) -> Result<[bool; 9], Error> {
+    let mut table = [false; 9];
+    for (neighbors, entry) in table.iter_mut().enumerate() {
+        *entry = f
+            .call1(&JsValue::NULL, &JsValue::from(neighbors as u32))
+            .map_err(|err| Error::new(format!("{} threw {:?}", name, err)))?
+            .is_truthy();
+    }
+    Ok(table)
+}
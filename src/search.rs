@@ -0,0 +1,239 @@
+//! Exhaustive search for small still lifes.
+//!
+//! Cells of the search box are decided one at a time in row-major order,
+//! alive or dead, with the box surrounded by dead cells. As soon as every
+//! cell around some cell has been decided, that cell is checked for
+//! stability, so most branches die after a handful of decisions. Each
+//! still life is reported once: only placements touching the top and left
+//! of the box are kept, and of the eight rotations and reflections of a
+//! pattern only the lexicographically smallest.
+
+use wasm_bindgen::prelude::*;
+
+use crate::rules::Rule;
+use crate::{utils, Cell, Error, Universe};
+
+/// The largest still life `find_still_lifes` will look for. The search
+/// grows roughly exponentially with this.
+pub const MAX_STILL_LIFE_CELLS: u32 = 8;
+
+// How many search nodes to visit between looks at the clock.
+const CLOCK_INTERVAL: u32 = 4096;
+
+#[wasm_bindgen]
+impl Universe {
+    /// `find_still_lifes` for JS, as an array of universes.
+    pub fn enumerate_still_lifes(
+        &self,
+        max_cells: u32,
+        timeout_ms: f64,
+    ) -> Result<js_sys::Array, Error> {
+        let found = self.find_still_lifes(max_cells, timeout_ms)?;
+        Ok(found.into_iter().map(JsValue::from).collect())
+    }
+}
+
+impl Universe {
+    /// Every still life under the current rule with 1 to `max_cells` live
+    /// cells that fits in a box the size of this board, up to translation,
+    /// rotation and reflection. Anything stable counts, including several
+    /// separate objects side by side. Each comes back in its own universe
+    /// with one dead cell of margin around it, smallest population first.
+    /// The board itself is ignored.
+    ///
+    /// The search recurses once per cell of the box, so a side may be at
+    /// most `max_cells + 2`, which is room enough for any still life of
+    /// `max_cells` cells with its margin; a larger board is an error. If
+    /// `timeout_ms` runs out first, the still lifes found so far are
+    /// returned.
+    pub fn find_still_lifes(
+        &self,
+        max_cells: u32,
+        timeout_ms: f64,
+    ) -> Result<Vec<Universe>, Error> {
+        if max_cells > MAX_STILL_LIFE_CELLS {
+            return Err(Error::new(format!(
+                "still lifes of up to {} cells can be searched for, not {}",
+                MAX_STILL_LIFE_CELLS, max_cells
+            )));
+        }
+        let max_side = max_cells + 2;
+        if self.width > max_side || self.height > max_side {
+            return Err(Error::new(format!(
+                "a search for {} cells needs a board at most {}x{}, not {}x{}",
+                max_cells, max_side, max_side, self.width, self.height
+            )));
+        }
+
+        let mut search = Search::new(self.width, self.height, self.rule, max_cells);
+        search.deadline = utils::now_ms() + timeout_ms;
+        search.run(0, 0);
+
+        let mut shapes = search.found;
+        shapes.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        Ok(shapes
+            .into_iter()
+            .map(|shape| {
+                let height = shape.iter().map(|&(row, _)| row).max().unwrap_or(0) + 3;
+                let width = shape.iter().map(|&(_, col)| col).max().unwrap_or(0) + 3;
+                let mut universe = Universe::empty(width, height);
+                universe.rule = self.rule;
+                let cells: Vec<(u32, u32)> =
+                    shape.iter().map(|&(row, col)| (row + 1, col + 1)).collect();
+                universe.set_cells(&cells);
+                universe
+            })
+            .collect())
+    }
+}
+
+struct Search {
+    width: u32,
+    height: u32,
+    rule: Rule,
+    max_cells: u32,
+    // The box with a dead border, (height + 2) x (width + 2).
+    alive: Vec<bool>,
+    // For each box cell, in search order, the bordered cells whose
+    // neighbourhood is complete once it has been decided.
+    checks: Vec<Vec<usize>>,
+    found: Vec<Vec<(u32, u32)>>,
+    deadline: f64,
+    visited: u32,
+    timed_out: bool,
+}
+
+impl Search {
+    fn new(width: u32, height: u32, rule: Rule, max_cells: u32) -> Search {
+        let padded_width = width + 2;
+        let mut checks = vec![Vec::new(); (width * height) as usize];
+        if width > 0 && height > 0 {
+            for row in 0..height + 2 {
+                for col in 0..padded_width {
+                    // The last box cell, in search order, next to this one.
+                    let last_row = (row + 1).min(height);
+                    let last_col = (col + 1).min(width);
+                    let decided_at = (last_row - 1) * width + (last_col - 1);
+                    checks[decided_at as usize].push((row * padded_width + col) as usize);
+                }
+            }
+        }
+
+        Search {
+            width,
+            height,
+            rule,
+            max_cells,
+            alive: vec![false; ((width + 2) * (height + 2)) as usize],
+            checks,
+            found: Vec::new(),
+            deadline: f64::INFINITY,
+            visited: 0,
+            timed_out: false,
+        }
+    }
+
+    fn run(&mut self, decided: u32, population: u32) {
+        self.visited += 1;
+        if self.visited.is_multiple_of(CLOCK_INTERVAL) && utils::now_ms() > self.deadline {
+            self.timed_out = true;
+        }
+        if self.timed_out {
+            return;
+        }
+        // Only placements touching the top of the box are wanted.
+        if decided == self.width && population == 0 {
+            return;
+        }
+        if decided == self.width * self.height {
+            self.record();
+            return;
+        }
+
+        let cell =
+            ((decided / self.width + 1) * (self.width + 2) + decided % self.width + 1) as usize;
+        for live in [false, true] {
+            if live && population == self.max_cells {
+                continue;
+            }
+            self.alive[cell] = live;
+            let stable = self.checks[decided as usize]
+                .iter()
+                .all(|&idx| self.is_stable(idx));
+            if stable {
+                self.run(decided + 1, population + live as u32);
+            }
+        }
+        self.alive[cell] = false;
+    }
+
+    fn is_stable(&self, idx: usize) -> bool {
+        let padded_width = (self.width + 2) as usize;
+        let (row, col) = (idx / padded_width, idx % padded_width);
+        let mut neighbors = 0;
+        for neighbor_row in row.saturating_sub(1)..=(row + 1).min(self.height as usize + 1) {
+            for neighbor_col in col.saturating_sub(1)..=(col + 1).min(padded_width - 1) {
+                let neighbor = neighbor_row * padded_width + neighbor_col;
+                if neighbor != idx && self.alive[neighbor] {
+                    neighbors += 1;
+                }
+            }
+        }
+        let current = if self.alive[idx] {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        };
+        self.rule.next(current, neighbors) == current
+    }
+
+    // Keeps the current board if it touches the left of the box and is the
+    // smallest of its orientations that fit the box.
+    fn record(&mut self) {
+        let padded_width = (self.width + 2) as usize;
+        let shape: Vec<(u32, u32)> = (0..self.width * self.height)
+            .filter(|&k| {
+                let (row, col) = (k / self.width + 1, k % self.width + 1);
+                self.alive[row as usize * padded_width + col as usize]
+            })
+            .map(|k| (k / self.width, k % self.width))
+            .collect();
+        if shape.is_empty() || shape.iter().all(|&(_, col)| col > 0) {
+            return;
+        }
+
+        let height = shape.iter().map(|&(row, _)| row).max().unwrap() + 1;
+        let width = shape.iter().map(|&(_, col)| col).max().unwrap() + 1;
+        let fits_transposed = height <= self.width && width <= self.height;
+        let smallest = (0..8)
+            .filter(|&orientation| orientation < 4 || fits_transposed)
+            .all(|orientation| {
+                let mut turned: Vec<(u32, u32)> = shape
+                    .iter()
+                    .map(|&(row, col)| orient(orientation, row, col, height, width))
+                    .collect();
+                turned.sort_unstable();
+                shape <= turned
+            });
+        if smallest {
+            self.found.push(shape);
+        }
+    }
+}
+
+// One of the eight symmetries of a `height` x `width` box: 0 is the
+// identity, 1-3 mirror and turn within the box, and 4-7 do the same after
+// swapping rows and columns.
+pub(crate) fn orient(orientation: u32, row: u32, col: u32, height: u32, width: u32) -> (u32, u32) {
+    let (row, col, height, width) = if orientation >= 4 {
+        (col, row, width, height)
+    } else {
+        (row, col, height, width)
+    };
+    match orientation % 4 {
+        0 => (row, col),
+        1 => (row, width - 1 - col),
+        2 => (height - 1 - row, col),
+        _ => (height - 1 - row, width - 1 - col),
+    }
+}
@@ -0,0 +1,48 @@
+//! serde support, behind the `serde` feature.
+//!
+//! A universe serializes as its width, height, generation, rule and the
+//! cells in `cells_packed` form, so the output stays small in binary
+//! formats. History, metadata and other per-session state are not saved.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::rules::Rule;
+use crate::Universe;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Universe")]
+struct Saved {
+    width: u32,
+    height: u32,
+    generation: u32,
+    rule: String,
+    cells: Vec<u8>,
+}
+
+impl Serialize for Universe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Saved {
+            width: self.width,
+            height: self.height,
+            generation: self.generation,
+            rule: self.rule.to_string(),
+            cells: self.cells_packed(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Universe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Universe, D::Error> {
+        let saved = Saved::deserialize(deserializer)?;
+        // Checked up front so untrusted sizes fail as a serde error.
+        crate::checked_cell_count(saved.width, saved.height)
+            .map_err(|e| D::Error::custom(e.message()))?;
+        let mut universe = Universe::from_packed(saved.width, saved.height, &saved.cells)
+            .map_err(|e| D::Error::custom(e.message()))?;
+        universe.rule = Rule::parse(&saved.rule).map_err(|e| D::Error::custom(e.message()))?;
+        universe.generation = saved.generation;
+        Ok(universe)
+    }
+}
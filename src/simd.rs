@@ -0,0 +1,93 @@
+//! A 128-bit SIMD tick for wasm targets built with `simd128`.
+//!
+//! Interior cells are processed 16 at a time: the eight neighbour rows are
+//! loaded as shifted byte vectors and summed lane by lane, then B3/S23 is
+//! applied with lane-wise comparisons. The first column and any columns too
+//! close to the right edge for a full vector fall back to the scalar path so
+//! wrapping is handled exactly as in `tick`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Identical to `tick`, but uses wasm SIMD when the crate is built with
+    /// `-C target-feature=+simd128`, no active region is set and the rule is
+    /// B3/S23. Otherwise it just calls `tick`.
+    pub fn tick_simd(&mut self) {
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        if self.active_region.is_none() && self.rule == crate::rules::Rule::CONWAY {
+            self.history.record(&self.cells);
+            self.step_simd();
+            return;
+        }
+
+        self.tick();
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+impl Universe {
+    fn step_simd(&mut self) -> (u32, u32) {
+        use std::arch::wasm32::*;
+
+        // Loads the 16 cells starting at `idx`.
+        unsafe fn load(cells: *const crate::Cell, idx: usize) -> v128 {
+            v128_load(cells.add(idx) as *const v128)
+        }
+
+        let width = self.width as usize;
+        let height = self.height as usize;
+        let cells = self.cells.as_ptr();
+        let mut next = self.cells.clone();
+        let mut births = 0;
+        let mut deaths = 0;
+
+        for row in 0..height {
+            let up = ((row + height - 1) % height) * width;
+            let mid = row * width;
+            let down = ((row + 1) % height) * width;
+
+            // Lanes col..col + 16 need columns col - 1..=col + 16 in bounds.
+            let mut col = 1;
+            while col + 16 < width {
+                // SAFETY: every load reads 16 bytes starting at most at
+                // `col + 1`, and `col + 16 < width` keeps them inside the row.
+                // `Cell` is `repr(u8)` and the lanes written back are 0 or 1.
+                unsafe {
+                    let mut count = i8x16_add(load(cells, up + col - 1), load(cells, up + col));
+                    count = i8x16_add(count, load(cells, up + col + 1));
+                    count = i8x16_add(count, load(cells, mid + col - 1));
+                    count = i8x16_add(count, load(cells, mid + col + 1));
+                    count = i8x16_add(count, load(cells, down + col - 1));
+                    count = i8x16_add(count, load(cells, down + col));
+                    count = i8x16_add(count, load(cells, down + col + 1));
+
+                    let alive = load(cells, mid + col);
+                    let born = v128_and(i8x16_eq(count, i8x16_splat(3)), i8x16_splat(1));
+                    let survives = v128_and(i8x16_eq(count, i8x16_splat(2)), alive);
+                    let next_cells = v128_or(born, survives);
+                    v128_store(next.as_mut_ptr().add(mid + col) as *mut v128, next_cells);
+
+                    // Negating turns 1 lanes into 0xff so bitmask picks them up.
+                    births += i8x16_bitmask(i8x16_neg(v128_andnot(next_cells, alive))).count_ones();
+                    deaths += i8x16_bitmask(i8x16_neg(v128_andnot(alive, next_cells))).count_ones();
+                }
+                col += 16;
+            }
+
+            for col in std::iter::once(0).chain(col..width) {
+                let idx = mid + col;
+                let cell = self.cells[idx];
+                let live_neighbors = self.live_neighbor_count(row as u32, col as u32);
+                let next_cell = self.next_state(cell, live_neighbors);
+                births += (next_cell as u8 > cell as u8) as u32;
+                deaths += ((next_cell as u8) < cell as u8) as u32;
+                next[idx] = next_cell;
+            }
+        }
+
+        self.finish_step(next, births, deaths)
+    }
+}
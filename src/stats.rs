@@ -0,0 +1,73 @@
+//! Per-tick statistics for HUDs, gathered in a single call.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// What happened on one tick, as returned by `Universe::tick_stats`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TickStats {
+    generation: u32,
+    population: u32,
+    births: u32,
+    deaths: u32,
+}
+
+#[wasm_bindgen]
+impl TickStats {
+    /// The generation reached by the tick.
+    #[wasm_bindgen(getter)]
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Live cells after the tick.
+    #[wasm_bindgen(getter)]
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn births(&self) -> u32 {
+        self.births
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn deaths(&self) -> u32 {
+        self.deaths
+    }
+
+    /// Cells that flipped state, i.e. births plus deaths.
+    #[wasm_bindgen(getter)]
+    pub fn changed(&self) -> u32 {
+        self.births + self.deaths
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Ticks once and reports the resulting statistics, saving the frontend
+    /// a round trip per value.
+    pub fn tick_stats(&mut self) -> TickStats {
+        self.history.record(&self.cells);
+        let (births, deaths) = self.step();
+        TickStats {
+            generation: self.generation,
+            population: self.population(),
+            births,
+            deaths,
+        }
+    }
+
+    /// A one-line summary for logs, e.g. `gen 128 | pop 342 | stable=false`.
+    /// The board counts as stable once a tick has left it unchanged.
+    pub fn status(&self) -> String {
+        format!(
+            "gen {} | pop {} | stable={}",
+            self.generation,
+            self.population(),
+            self.generations_since_change > 0
+        )
+    }
+}
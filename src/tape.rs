@@ -0,0 +1,57 @@
+//! Binary tapes stored as a single row of cells, for building Turing
+//! machine experiments on top of the board.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Writes `states` into `row` as live (non-zero) and dead (zero) cells,
+    /// centred horizontally. The rest of the row is cleared.
+    pub fn create_turing_tape(&mut self, states: &[u8], row: u32) -> Result<(), Error> {
+        self.check_tape_row(row)?;
+        if states.len() > self.width as usize {
+            return Err(Error::new(format!(
+                "a tape of {} cells does not fit in a row of {}",
+                states.len(),
+                self.width
+            )));
+        }
+
+        let start = self.get_index(row, 0);
+        let tape = &mut self.cells[start..start + self.width as usize];
+        let offset = (tape.len() - states.len()) / 2;
+        tape.iter_mut().for_each(|cell| *cell = Cell::Dead);
+        for (cell, &state) in tape[offset..].iter_mut().zip(states) {
+            if state != 0 {
+                *cell = Cell::Alive;
+            }
+        }
+        self.cells_edited();
+        Ok(())
+    }
+
+    /// Every cell of `row` as 1 (alive) or 0 (dead), left to right. A tape
+    /// shorter than the board comes back with its dead margins.
+    pub fn read_turing_tape(&self, row: u32) -> Result<Box<[u8]>, Error> {
+        self.check_tape_row(row)?;
+        let start = self.get_index(row, 0);
+        Ok(self.cells[start..start + self.width as usize]
+            .iter()
+            .map(Cell::as_byte)
+            .collect())
+    }
+}
+
+impl Universe {
+    fn check_tape_row(&self, row: u32) -> Result<(), Error> {
+        if row >= self.height {
+            return Err(Error::new(format!(
+                "row {} is outside a universe of height {}",
+                row, self.height
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! RGBA pixel data for drawing the board on a canvas or the GPU.
+//!
+//! WebGL reads texture rows starting on `UNPACK_ALIGNMENT` boundaries, so
+//! `export_webgl_texture` pads each row to the alignment the caller asks
+//! for and the buffer can go straight to `texImage2D`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+// Opaque RGBA, black cells on white like the canvas demo.
+const ALIVE_RGBA: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+const DEAD_RGBA: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+#[wasm_bindgen]
+impl Universe {
+    /// The board as RGBA pixels with each cell drawn as a `cell_size` x
+    /// `cell_size` square, live cells black and dead ones white. Each row of
+    /// `width() * cell_size * 4` bytes is padded with zeros to the next
+    /// multiple of `align_stride` bytes, so there are `height() * cell_size`
+    /// rows of that padded length.
+    pub fn export_webgl_texture(
+        &self,
+        cell_size: u32,
+        align_stride: u32,
+    ) -> Result<Box<[u8]>, Error> {
+        if cell_size == 0 || align_stride == 0 {
+            return Err(Error::new(format!(
+                "cell size and stride alignment must be positive, got {} and {}",
+                cell_size, align_stride
+            )));
+        }
+
+        let too_large = || {
+            Error::new(format!(
+                "a {}x{} texture at cell size {} is too large",
+                self.width, self.height, cell_size
+            ))
+        };
+        let cell_size = cell_size as usize;
+        let row_bytes = (self.width as usize)
+            .checked_mul(cell_size)
+            .and_then(|pixels| pixels.checked_mul(4))
+            .ok_or_else(too_large)?;
+        let stride = row_bytes
+            .checked_next_multiple_of(align_stride as usize)
+            .ok_or_else(too_large)?;
+        let len = (self.height as usize)
+            .checked_mul(cell_size)
+            .and_then(|rows| rows.checked_mul(stride))
+            .ok_or_else(too_large)?;
+        let mut texture = vec![0u8; len];
+        if stride == 0 {
+            return Ok(texture.into_boxed_slice());
+        }
+        for (row, line) in texture.chunks_exact_mut(stride).enumerate() {
+            let cells_row = (row / cell_size) as u32;
+            for (col, pixel) in line[..row_bytes].chunks_exact_mut(4).enumerate() {
+                let cell = self.cells[self.get_index(cells_row, (col / cell_size) as u32)];
+                pixel.copy_from_slice(if cell == Cell::Alive {
+                    &ALIVE_RGBA
+                } else {
+                    &DEAD_RGBA
+                });
+            }
+        }
+        Ok(texture.into_boxed_slice())
+    }
+
+    /// One RGBA pixel per cell, with live cells shaded by age: a cell of age
+    /// 0 is `young_color`, one of `max_age` or more is `old_color`, and
+    /// those in between are interpolated linearly. Dead cells are white.
+    /// `ages` holds one age per cell in the same order as `cells()`;
+    /// colours are `0xRRGGBB` and the image is opaque.
+    pub fn render_age_colored(
+        &self,
+        ages: &[u32],
+        young_color: u32,
+        old_color: u32,
+        max_age: u32,
+    ) -> Result<Box<[u8]>, Error> {
+        if ages.len() != self.cells.len() {
+            return Err(Error::new(format!(
+                "expected {} ages for a {}x{} universe, got {}",
+                self.cells.len(),
+                self.width,
+                self.height,
+                ages.len()
+            )));
+        }
+
+        let mut pixels = Vec::with_capacity(self.cells.len() * 4);
+        for (&cell, &age) in self.cells.iter().zip(ages) {
+            if cell == Cell::Dead {
+                pixels.extend_from_slice(&DEAD_RGBA);
+                continue;
+            }
+            let t = if max_age == 0 {
+                1.0
+            } else {
+                age.min(max_age) as f64 / max_age as f64
+            };
+            for shift in [16, 8, 0] {
+                let young = (young_color >> shift & 0xff) as f64;
+                let old = (old_color >> shift & 0xff) as f64;
+                pixels.push((young + (old - young) * t).round() as u8);
+            }
+            pixels.push(0xff);
+        }
+        Ok(pixels.into_boxed_slice())
+    }
+}
@@ -0,0 +1,146 @@
+//! A minimal lossless WebP (VP8L) encoder for rendering the board.
+//!
+//! With only two colours on the board every channel takes at most two
+//! values, so each of the five VP8L prefix codes fits the "simple code" form
+//! and every pixel is written as up to four single-bit literals. No
+//! transforms, colour cache or backward references are used. The output is
+//! not as small as a full encoder would produce, but it is a valid
+//! `RIFF`/`WEBP`/`VP8L` file that every decoder accepts.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Error, Universe};
+
+// The largest width or height VP8L can describe.
+const MAX_DIMENSION: u32 = 1 << 14;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Encodes the board as a lossless WebP image with each cell drawn as a
+    /// `cell_px` x `cell_px` square. Colours are `0xRRGGBB`; the image is
+    /// opaque.
+    pub fn save_as_webp(
+        &self,
+        alive_color: u32,
+        dead_color: u32,
+        cell_px: u32,
+    ) -> Result<Box<[u8]>, Error> {
+        let too_large = || {
+            Error::new(format!(
+                "a {}x{} board at {} pixels per cell can't be stored as WebP",
+                self.width, self.height, cell_px
+            ))
+        };
+        let width = self.width.checked_mul(cell_px).ok_or_else(too_large)?;
+        let height = self.height.checked_mul(cell_px).ok_or_else(too_large)?;
+        if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return Err(too_large());
+        }
+
+        let mut bits = BitWriter::default();
+        bits.write(0x2f, 8);
+        bits.write(width - 1, 14);
+        bits.write(height - 1, 14);
+        bits.write(0, 1); // alpha is unused
+        bits.write(0, 3); // version
+        bits.write(0, 1); // no transforms
+        bits.write(0, 1); // no colour cache
+        bits.write(0, 1); // a single set of prefix codes
+
+        // Green, red, blue, alpha and distance, in bitstream order.
+        let channel = |color: u32, shift: u32| (color >> shift & 0xff) as u8;
+        let codes = [
+            SimpleCode::new(channel(dead_color, 8), channel(alive_color, 8)),
+            SimpleCode::new(channel(dead_color, 16), channel(alive_color, 16)),
+            SimpleCode::new(channel(dead_color, 0), channel(alive_color, 0)),
+            SimpleCode::new(0xff, 0xff),
+            SimpleCode::new(0, 0),
+        ];
+        for code in &codes {
+            code.write_header(&mut bits);
+        }
+
+        for row in 0..height {
+            for col in 0..width {
+                let cell = self.cells[self.get_index(row / cell_px, col / cell_px)];
+                let color = if cell == Cell::Alive {
+                    alive_color
+                } else {
+                    dead_color
+                };
+                codes[0].write_symbol(&mut bits, channel(color, 8));
+                codes[1].write_symbol(&mut bits, channel(color, 16));
+                codes[2].write_symbol(&mut bits, channel(color, 0));
+            }
+        }
+
+        let vp8l = bits.finish();
+        let padding = vp8l.len() % 2;
+        let mut out = Vec::with_capacity(20 + vp8l.len() + padding);
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(12 + vp8l.len() as u32 + padding as u32).to_le_bytes());
+        out.extend_from_slice(b"WEBPVP8L");
+        out.extend_from_slice(&(vp8l.len() as u32).to_le_bytes());
+        out.extend_from_slice(&vp8l);
+        out.resize(out.len() + padding, 0);
+        Ok(out.into_boxed_slice())
+    }
+}
+
+// A VP8L "simple" prefix code over at most two 8-bit symbols. One symbol
+// costs no bits per pixel; with two, the smaller is coded 0 and the larger 1.
+struct SimpleCode {
+    symbols: (u8, Option<u8>),
+}
+
+impl SimpleCode {
+    fn new(a: u8, b: u8) -> SimpleCode {
+        SimpleCode {
+            symbols: if a == b {
+                (a, None)
+            } else {
+                (a.min(b), Some(a.max(b)))
+            },
+        }
+    }
+
+    fn write_header(&self, bits: &mut BitWriter) {
+        bits.write(1, 1); // simple code
+        bits.write(self.symbols.1.is_some() as u32, 1);
+        bits.write(1, 1); // first symbol is 8 bits wide
+        bits.write(self.symbols.0 as u32, 8);
+        if let Some(second) = self.symbols.1 {
+            bits.write(second as u32, 8);
+        }
+    }
+
+    fn write_symbol(&self, bits: &mut BitWriter, symbol: u8) {
+        if self.symbols.1.is_some() {
+            bits.write((symbol != self.symbols.0) as u32, 1);
+        }
+    }
+}
+
+// Packs values least significant bit first, as VP8L requires.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    used: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: u32) {
+        for i in 0..count {
+            if self.used.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = (value >> i & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (self.used % 8);
+            self.used += 1;
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
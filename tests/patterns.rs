@@ -0,0 +1,627 @@
+//! Placement and behaviour of the built-in pattern spawners.
+
+use wasm_game_of_life::{Axis, Cell, PatternKind, Symmetry, Universe, GLIDER};
+
+fn live_cells(universe: &Universe) -> Vec<(u32, u32)> {
+    let width = universe.width();
+    universe
+        .get_cells()
+        .iter()
+        .enumerate()
+        .filter(|&(_, &cell)| cell == Cell::Alive)
+        .map(|(idx, _)| (idx as u32 / width, idx as u32 % width))
+        .collect()
+}
+
+const PULSAR_AT_16_16: [(u32, u32); 48] = [
+    (10, 12),
+    (10, 13),
+    (10, 14),
+    (10, 18),
+    (10, 19),
+    (10, 20),
+    (12, 10),
+    (12, 15),
+    (12, 17),
+    (12, 22),
+    (13, 10),
+    (13, 15),
+    (13, 17),
+    (13, 22),
+    (14, 10),
+    (14, 15),
+    (14, 17),
+    (14, 22),
+    (15, 12),
+    (15, 13),
+    (15, 14),
+    (15, 18),
+    (15, 19),
+    (15, 20),
+    (17, 12),
+    (17, 13),
+    (17, 14),
+    (17, 18),
+    (17, 19),
+    (17, 20),
+    (18, 10),
+    (18, 15),
+    (18, 17),
+    (18, 22),
+    (19, 10),
+    (19, 15),
+    (19, 17),
+    (19, 22),
+    (20, 10),
+    (20, 15),
+    (20, 17),
+    (20, 22),
+    (22, 12),
+    (22, 13),
+    (22, 14),
+    (22, 18),
+    (22, 19),
+    (22, 20),
+];
+
+#[test]
+fn glider_places_five_cells() {
+    let mut universe = Universe::empty(16, 16);
+    universe.add_glider(5, 5);
+    assert_eq!(
+        live_cells(&universe),
+        vec![(4, 5), (5, 6), (6, 4), (6, 5), (6, 6)]
+    );
+}
+
+#[test]
+fn glider_moves_diagonally_every_four_ticks() {
+    let mut universe = Universe::empty(16, 16);
+    universe.add_glider(5, 5);
+
+    for _ in 0..4 {
+        universe.tick();
+    }
+    assert_eq!(
+        live_cells(&universe),
+        vec![(5, 6), (6, 7), (7, 5), (7, 6), (7, 7)]
+    );
+}
+
+#[test]
+fn glider_wraps_around_the_corner() {
+    let mut universe = Universe::empty(16, 16);
+    universe.add_glider(0, 0);
+    assert_eq!(
+        live_cells(&universe),
+        vec![(0, 1), (1, 0), (1, 1), (1, 15), (15, 0)]
+    );
+}
+
+#[test]
+fn pulsar_places_forty_eight_cells() {
+    let mut universe = Universe::empty(32, 32);
+    universe.add_pulsar(16, 16);
+    assert_eq!(live_cells(&universe), PULSAR_AT_16_16.to_vec());
+}
+
+#[test]
+fn pulsar_has_period_three() {
+    let mut universe = Universe::empty(32, 32);
+    universe.add_pulsar(16, 16);
+    let start = universe.get_cells().to_vec();
+
+    for _ in 0..2 {
+        universe.tick();
+        assert_ne!(universe.get_cells(), &start[..]);
+    }
+    universe.tick();
+    assert_eq!(universe.get_cells(), &start[..]);
+}
+
+#[test]
+fn glider_collider_places_and_destroys_pairs() {
+    for &axis in &[Axis::Horizontal, Axis::Vertical] {
+        let mut universe = Universe::empty(64, 64);
+        universe.make_glider_collider(2, axis);
+        assert_eq!(universe.population(), 5 * 2 * 2);
+
+        for _ in 0..200 {
+            universe.tick();
+        }
+        assert!(universe.population() < 20, "{:?}", axis);
+    }
+}
+
+#[test]
+fn glider_collider_spreads_many_pairs_across_a_wide_board() {
+    let mut universe = Universe::empty(70000, 8);
+    universe.make_glider_collider(40000, Axis::Vertical);
+    assert!(universe.population() > 0);
+}
+
+#[test]
+fn wrapped_glider_recurs_but_is_a_spaceship() {
+    let mut universe = Universe::empty(8, 8);
+    universe.add_glider(3, 3);
+
+    // On the 8x8 torus the glider comes back to where it started...
+    assert_eq!(universe.detect_period(40), Some(32));
+    // ...but in isolation it is a period-4 spaceship, not an oscillator.
+    assert_eq!(universe.classify(40), PatternKind::Spaceship);
+    assert_eq!(
+        universe.classify_with_period(40),
+        (PatternKind::Spaceship, Some(4))
+    );
+}
+
+#[test]
+fn glider_straddling_the_edge_is_a_spaceship() {
+    let mut universe = Universe::empty(8, 8);
+    universe.add_glider(0, 0);
+    assert_eq!(universe.classify(8), PatternKind::Spaceship);
+}
+
+#[test]
+fn classify_still_lifes_and_oscillators() {
+    let mut block = Universe::empty(8, 8);
+    block.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert_eq!(block.classify(10), PatternKind::StillLife);
+    assert_eq!(block.detect_period(10), Some(1));
+
+    let mut blinker = Universe::empty(8, 8);
+    blinker.set_cells(&[(3, 2), (3, 3), (3, 4)]);
+    assert_eq!(
+        blinker.classify_with_period(10),
+        (PatternKind::Oscillator, Some(2))
+    );
+
+    assert_eq!(Universe::empty(8, 8).classify(10), PatternKind::Empty);
+
+    // Under B3/S2 a block loses its survival on 3 and falls apart.
+    block.set_rule("B3/S2").unwrap();
+    assert_ne!(block.classify(10), PatternKind::StillLife);
+}
+
+#[test]
+fn classify_caps_the_period_it_searches() {
+    let mut blinker = Universe::empty(8, 8);
+    blinker.set_cells(&[(3, 2), (3, 3), (3, 4)]);
+    assert_eq!(
+        blinker.classify_with_period(u32::MAX),
+        (PatternKind::Oscillator, Some(2))
+    );
+}
+
+#[test]
+fn infinite_growth_keeps_building() {
+    let mut universe = Universe::empty(256, 256);
+    universe.add_infinite_growth(128, 128);
+    assert_eq!(universe.population(), 10);
+
+    for _ in 0..400 {
+        universe.tick();
+    }
+    let early = universe.population();
+    assert!(early > 100, "{}", early);
+
+    // The engine is still running rather than settling into ash.
+    for _ in 0..500 {
+        universe.tick();
+    }
+    assert!(universe.population() > early, "{}", universe.population());
+    assert_eq!(universe.detect_period(30), None);
+}
+
+#[test]
+fn eater_1_swallows_a_glider() {
+    let mut universe = Universe::empty(32, 32);
+    universe.add_eater_1(16, 16);
+    let eater = live_cells(&universe);
+    universe.add_glider(8, 8);
+
+    for _ in 0..50 {
+        universe.tick();
+    }
+    assert_eq!(live_cells(&universe), eater);
+}
+
+#[test]
+fn queen_bee_shuttle_has_period_thirty() {
+    let mut universe = Universe::empty(48, 32);
+    universe.add_queen_bee_shuttle(16, 24);
+    assert_eq!(universe.population(), 20);
+    let start = universe.get_cells().to_vec();
+
+    for _ in 1..30 {
+        universe.tick();
+        assert_ne!(universe.get_cells(), &start[..]);
+    }
+    universe.tick();
+    assert_eq!(universe.get_cells(), &start[..]);
+}
+
+#[test]
+fn p60_oscillator_has_period_sixty() {
+    let mut universe = Universe::empty(41, 13);
+    universe.add_p60_oscillator(3, 3);
+    assert_eq!(universe.population(), 29);
+    assert_eq!(universe.detect_period(65), Some(60));
+
+    let start = universe.get_cells().to_vec();
+    for generation in 1..60 {
+        universe.tick();
+        assert_ne!(
+            universe.get_cells(),
+            &start[..],
+            "generation {}",
+            generation
+        );
+    }
+    universe.tick();
+    assert_eq!(universe.get_cells(), &start[..]);
+
+    // It is the glider that sets the period: without it the pentadecathlons
+    // repeat every 15 generations.
+    let mut still = Universe::empty(41, 13);
+    still.add_p60_oscillator(3, 3);
+    still.erase_pattern(6, 19, &[(0, 0), (1, 1), (1, 2), (2, 0), (2, 1)]);
+    assert_eq!(still.population(), 24);
+    assert_eq!(still.detect_period(65), Some(15));
+}
+
+#[test]
+fn spawners_are_safe_on_tiny_boards() {
+    let mut one = Universe::empty(1, 1);
+    one.add_glider(0, 0);
+    one.add_pulsar(-5, 7);
+    assert_eq!(live_cells(&one), vec![(0, 0)]);
+    // All eight neighbours are the cell itself, so it dies of overcrowding.
+    one.tick();
+    assert_eq!(one.population(), 0);
+
+    let mut two = Universe::empty(2, 2);
+    two.add_glider(0, 0);
+    assert_eq!(live_cells(&two), vec![(0, 1), (1, 0), (1, 1)]);
+    two.tick();
+
+    let mut none = Universe::empty(0, 0);
+    none.add_glider(0, 0);
+    none.make_glider_collider(2, Axis::Vertical);
+    none.tick();
+    assert_eq!(none.population(), 0);
+}
+
+#[test]
+fn copperhead_moves_up_a_row_every_ten_ticks() {
+    let mut universe = Universe::empty(32, 32);
+    universe.add_copperhead(16, 16);
+    let start = live_cells(&universe);
+    assert_eq!(start.len(), 28);
+
+    for _ in 0..10 {
+        universe.tick();
+    }
+    let moved: Vec<_> = start.iter().map(|&(row, col)| (row - 1, col)).collect();
+    assert_eq!(live_cells(&universe), moved);
+}
+
+#[test]
+fn weekender_moves_two_rows_every_seven_ticks() {
+    let mut universe = Universe::empty(128, 128);
+    universe.add_weekender(64, 64);
+    let start = live_cells(&universe);
+    assert_eq!(start.len(), 36);
+
+    for _ in 0..7 {
+        universe.tick();
+    }
+    let moved: Vec<_> = start.iter().map(|&(row, col)| (row - 2, col)).collect();
+    assert_eq!(live_cells(&universe), moved);
+}
+
+#[test]
+fn pulsar_population_has_period_three() {
+    let mut universe = Universe::empty(32, 32);
+    universe.add_pulsar(16, 16);
+    assert_eq!(universe.population_period(10), Some(3));
+    assert_eq!(universe.generation(), 20);
+
+    let mut glider = Universe::empty(16, 16);
+    glider.add_glider(8, 8);
+    assert_eq!(glider.population_period(10), Some(1));
+
+    let mut growth = Universe::empty(64, 64);
+    growth.add_infinite_growth(32, 32);
+    assert_eq!(growth.population_period(5), None);
+}
+
+#[test]
+fn boundary_modes_only_differ_near_the_edges() {
+    let mut centred = Universe::empty(32, 32);
+    centred.add_pulsar(16, 16);
+    centred.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert_eq!(centred.count_difference_between_boundary_modes(20), 0);
+
+    // Heading into the bottom-right corner, the glider wraps on the torus
+    // but crashes into the wall otherwise.
+    let mut cornered = Universe::empty(16, 16);
+    cornered.add_glider(12, 12);
+    assert_eq!(cornered.count_difference_between_boundary_modes(2), 0);
+    assert!(cornered.count_difference_between_boundary_modes(20) > 0);
+    assert_eq!(cornered.generation(), 0);
+}
+
+#[test]
+fn census_counts_blocks_blinkers_and_gliders() {
+    let mut universe = Universe::empty(48, 48);
+    for &(row, col) in &[(6, 6), (2, 20), (30, 40)] {
+        universe.set_cells(&[
+            (row, col),
+            (row, col + 1),
+            (row + 1, col),
+            (row + 1, col + 1),
+        ]);
+    }
+    for &(row, col) in &[(12, 5), (40, 10)] {
+        universe.set_cells(&[(row, col), (row, col + 1), (row, col + 2)]);
+    }
+    universe.add_pulsar(20, 28);
+    // Across the corner, so the object wraps around both edges.
+    universe.add_glider(0, 0);
+
+    let report = universe.run_census();
+    assert_eq!(report.population(), 3 * 4 + 2 * 3 + 48 + 5);
+    assert_eq!(report.still_lifes(), 3);
+    assert_eq!(report.oscillators(), 3);
+    assert_eq!(&*report.oscillator_periods(), &[2, 3]);
+    assert_eq!(report.oscillators_with_period(2), 2);
+    assert_eq!(report.oscillators_with_period(3), 1);
+    assert_eq!(report.spaceships(), 1);
+    assert_eq!(report.unknown(), 0);
+
+    assert_eq!(Universe::empty(8, 8).run_census(), Default::default());
+}
+
+#[test]
+fn switch_engine_grows() {
+    let mut universe = Universe::empty(128, 128);
+    universe.add_switch_engine(64, 64);
+    assert_eq!(universe.population(), 8);
+
+    for _ in 0..1000 {
+        universe.tick();
+    }
+    assert!(universe.population() > 400, "{}", universe.population());
+    assert_eq!(universe.detect_period(30), None);
+}
+
+#[test]
+fn pulsar_is_fully_symmetric() {
+    let mut universe = Universe::empty(32, 32);
+    universe.add_pulsar(10, 20);
+    assert_eq!(universe.detect_symmetry(), Symmetry::Both);
+    universe.tick();
+    assert_eq!(universe.detect_symmetry(), Symmetry::Both);
+}
+
+#[test]
+fn glider_has_no_symmetry() {
+    let mut universe = Universe::empty(16, 16);
+    universe.add_glider(8, 8);
+    assert_eq!(universe.detect_symmetry(), Symmetry::None);
+}
+
+#[test]
+fn symmetry_distinguishes_mirrors_from_half_turns() {
+    let mut tee = Universe::empty(8, 8);
+    tee.set_cells(&[(2, 2), (2, 3), (2, 4), (3, 3)]);
+    assert_eq!(tee.detect_symmetry(), Symmetry::Horizontal);
+
+    let mut column = Universe::empty(8, 8);
+    column.set_cells(&[(2, 2), (3, 2), (4, 2), (3, 3)]);
+    assert_eq!(column.detect_symmetry(), Symmetry::Vertical);
+
+    let mut zigzag = Universe::empty(8, 8);
+    zigzag.set_cells(&[(2, 2), (2, 3), (3, 3), (3, 4)]);
+    assert_eq!(zigzag.detect_symmetry(), Symmetry::Rotational);
+}
+
+#[test]
+fn still_lifes_of_four_cells_are_block_and_tub() {
+    let block: Vec<(u32, u32)> = vec![(1, 1), (1, 2), (2, 1), (2, 2)];
+    let tub: Vec<(u32, u32)> = vec![(1, 2), (2, 1), (2, 3), (3, 2)];
+
+    let found = Universe::empty(6, 6).find_still_lifes(4, 10_000.0).unwrap();
+    let shapes: Vec<Vec<(u32, u32)>> = found.iter().map(live_cells).collect();
+    assert_eq!(shapes, vec![block, tub]);
+    assert!(found
+        .iter()
+        .all(|still_life| still_life.detect_period(1) == Some(1)));
+}
+
+#[test]
+fn still_lifes_of_six_cells_include_the_beehive() {
+    let beehive: Vec<(u32, u32)> = vec![(1, 2), (1, 3), (2, 1), (2, 4), (3, 2), (3, 3)];
+
+    let found = Universe::empty(6, 6).find_still_lifes(6, 10_000.0).unwrap();
+    let populations: Vec<u32> = found.iter().map(|s| s.population()).collect();
+    // Block, tub, boat, then the beehive, ship, snake, carrier and barge.
+    assert_eq!(populations, [4, 4, 5, 6, 6, 6, 6, 6]);
+    assert!(found.iter().any(|s| live_cells(s) == beehive));
+    assert!(!Universe::empty(6, 6)
+        .find_still_lifes(4, 10_000.0)
+        .unwrap()
+        .iter()
+        .any(|s| live_cells(s) == beehive));
+
+    assert!(Universe::empty(6, 6).find_still_lifes(9, 1.0).is_err());
+    assert!(Universe::empty(64, 64).find_still_lifes(6, 1.0).is_err());
+    assert!(Universe::empty(7, 6).find_still_lifes(4, 1.0).is_err());
+}
+
+#[test]
+fn contains_pattern_finds_a_lone_glider() {
+    let mut universe = Universe::empty(16, 16);
+    assert!(!universe.contains_pattern(&GLIDER));
+
+    // Straddling the corner, so the match has to wrap.
+    universe.add_glider(0, 15);
+    assert!(universe.contains_pattern(&GLIDER));
+
+    // Two generations on, the glider is in its mirrored phase.
+    universe.tick();
+    universe.tick();
+    assert!(!universe.contains_pattern(&GLIDER));
+    assert!(universe.contains_pattern_in_any_orientation(&GLIDER));
+}
+
+#[test]
+fn contains_pattern_ignores_patterns_inside_larger_ones() {
+    let mut universe = Universe::empty(16, 16);
+    universe.add_glider(8, 8);
+    universe.set_cells(&[(7, 7)]);
+    assert!(!universe.contains_pattern(&GLIDER));
+    assert!(!Universe::empty(4, 4).contains_pattern(&[]));
+}
+
+#[test]
+fn p14_oscillator_has_period_fourteen() {
+    let mut universe = Universe::empty(16, 16);
+    universe.add_p14_oscillator(6, 8);
+    assert_eq!(universe.population(), 16);
+    assert_eq!(universe.detect_period(15), Some(14));
+
+    // Never stable: every tick changes the board.
+    let mut populations = Vec::new();
+    for _ in 0..28 {
+        populations.push(universe.population());
+        universe.tick();
+        assert_eq!(universe.generations_since_change(), 0);
+    }
+    assert_eq!(populations[..14], populations[14..]);
+}
+
+#[test]
+fn gosper_gun_fires_a_glider_every_thirty_generations() {
+    let mut universe = Universe::empty(80, 80);
+    universe.add_gosper_glider_gun(2, 2);
+    assert_eq!(universe.population(), 36);
+
+    for _ in 0..60 {
+        universe.tick();
+    }
+    let before = universe.population();
+    for _ in 0..30 {
+        universe.tick();
+    }
+    assert_eq!(universe.population(), before + 5);
+}
+
+#[test]
+fn opposing_guns_settle_into_period_thirty() {
+    let mut universe = Universe::empty(80, 80);
+    universe.add_opposing_gun_pair(4, 4, 60).unwrap();
+    assert_eq!(universe.population(), 72);
+
+    for _ in 0..600 {
+        universe.tick();
+    }
+    assert_eq!(universe.detect_period(30), Some(30));
+    let populations: Vec<u32> = (0..60)
+        .map(|_| {
+            universe.tick();
+            universe.population()
+        })
+        .collect();
+    assert_eq!(populations[..30], populations[30..]);
+    assert_ne!(populations[0], populations[1]);
+}
+
+#[test]
+fn opposing_gun_separation_rounds_down_to_a_clean_spacing() {
+    let place = |separation| {
+        let mut universe = Universe::empty(80, 80);
+        universe.add_opposing_gun_pair(4, 4, separation).unwrap();
+        live_cells(&universe)
+    };
+    assert_eq!(place(45), place(41));
+    assert_ne!(place(41), place(40));
+    assert!(Universe::empty(80, 80)
+        .add_opposing_gun_pair(4, 4, 31)
+        .is_err());
+}
+
+#[test]
+fn opposing_guns_wrap_from_extreme_anchors() {
+    // An anchor congruent to 4 on an 80-cell board places the same pair.
+    let mut reference = Universe::empty(80, 80);
+    reference.add_opposing_gun_pair(4, 4, 60).unwrap();
+    let anchor = i32::MAX - (i32::MAX % 80) + 4;
+    let mut universe = Universe::empty(80, 80);
+    universe.add_opposing_gun_pair(anchor, anchor, 60).unwrap();
+    assert_eq!(live_cells(&universe), live_cells(&reference));
+
+    let mut universe = Universe::empty(80, 80);
+    universe
+        .add_opposing_gun_pair(i32::MIN, i32::MAX, u32::MAX)
+        .unwrap();
+    // The wrapped guns may overlap, but each is placed whole.
+    assert!((36..=72).contains(&universe.population()));
+
+    let mut empty = Universe::empty(0, 0);
+    empty.add_opposing_gun_pair(4, 4, 60).unwrap();
+    assert_eq!(empty.population(), 0);
+}
+
+#[test]
+fn herschel_settles_after_128_generations() {
+    let mut universe = Universe::empty(100, 100);
+    universe.add_herschel(50, 50);
+    assert_eq!(universe.population(), 7);
+
+    let mut populations = Vec::new();
+    for _ in 0..200 {
+        universe.tick();
+        populations.push(universe.population());
+    }
+    // populations[i] is the population at generation i + 1.
+    assert_ne!(populations[126], populations[127]);
+    assert!(populations[127..]
+        .iter()
+        .all(|&population| population == 24));
+    assert!(universe.contains_pattern_in_any_orientation(&GLIDER));
+}
+
+// The fingerprint `ash_census` gives the lone object made of `cells`.
+fn ash_fingerprint(cells: &[(u32, u32)]) -> u64 {
+    let mut universe = Universe::empty(12, 12);
+    universe.set_cells(cells);
+    let census = universe.ash_census(0);
+    assert_eq!(census.len(), 1);
+    assert_eq!(census[0].1, 1);
+    census[0].0
+}
+
+#[test]
+fn ash_census_counts_settled_objects() {
+    let block = ash_fingerprint(&[(5, 5), (5, 6), (6, 5), (6, 6)]);
+    let beehive = ash_fingerprint(&[(3, 4), (4, 3), (4, 5), (5, 3), (5, 5), (6, 4)]);
+    let blinker = ash_fingerprint(&[(4, 5), (5, 5), (6, 5)]);
+    assert_ne!(block, beehive);
+
+    let mut universe = Universe::empty(32, 32);
+    // Two L-trominoes become blocks, and a line of four becomes a beehive.
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2)]);
+    universe.set_cells(&[(20, 6), (21, 5), (21, 6)]);
+    universe.set_cells(&[(10, 20), (10, 21), (11, 20), (11, 21)]);
+    universe.set_cells(&[(15, 2), (15, 3), (15, 4), (15, 5)]);
+    universe.set_cells(&[(25, 25), (25, 26), (25, 27)]);
+
+    let mut expected = vec![(block, 3), (beehive, 1), (blinker, 1)];
+    expected.sort_unstable();
+    assert_eq!(universe.ash_census(100), expected);
+    assert!(universe.generation() < 10);
+
+    assert_eq!(Universe::empty(8, 8).ash_census(10), []);
+}
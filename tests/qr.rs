@@ -0,0 +1,53 @@
+//! QR-like seeding from text.
+
+use wasm_game_of_life::{Cell, Universe};
+
+fn row_string(universe: &Universe, row: u32) -> String {
+    let width = universe.width() as usize;
+    universe.get_cells()[row as usize * width..][..width]
+        .iter()
+        .map(|&cell| if cell == Cell::Alive { '#' } else { '.' })
+        .collect()
+}
+
+#[test]
+fn same_text_gives_the_same_board() {
+    let first = Universe::from_qr_code("hello, life", 0).unwrap();
+    let second = Universe::from_qr_code("hello, life", 0).unwrap();
+    assert_eq!(first.get_cells(), second.get_cells());
+
+    let other = Universe::from_qr_code("hello, lift", 0).unwrap();
+    assert_ne!(first.get_cells(), other.get_cells());
+}
+
+#[test]
+fn short_text_fits_the_smallest_matrix() {
+    // 21 modules plus a 4-cell quiet zone on each side.
+    let universe = Universe::from_qr_code("hello", 0).unwrap();
+    assert_eq!((universe.width(), universe.height()), (29, 29));
+
+    // The top of the top-left and top-right finders.
+    assert_eq!(&row_string(&universe, 4)[..12], "....#######.");
+    assert_eq!(&row_string(&universe, 4)[17..], ".#######....");
+    assert_eq!(&row_string(&universe, 6)[..12], "....#.###.#.");
+}
+
+#[test]
+fn min_width_pads_around_the_matrix() {
+    let small = Universe::from_qr_code("hello", 0).unwrap();
+    let padded = Universe::from_qr_code("hello", 41).unwrap();
+    assert_eq!((padded.width(), padded.height()), (41, 41));
+    assert_eq!(padded.population(), small.population());
+    assert_eq!(&row_string(&padded, 10)[..18], "..........#######.");
+
+    assert!(Universe::from_qr_code("hello", 70000).is_err());
+    assert!(Universe::from_qr_code("hello", 8193).is_err());
+}
+
+#[test]
+fn longer_text_uses_a_larger_matrix() {
+    let text = "x".repeat(100);
+    let universe = Universe::from_qr_code(&text, 0).unwrap();
+    assert!(universe.width() > 29);
+    assert!(Universe::from_qr_code(&"x".repeat(256), 0).is_err());
+}
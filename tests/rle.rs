@@ -0,0 +1,224 @@
+//! Reading and writing run-length encoded patterns.
+
+use wasm_game_of_life::{Cell, Universe};
+
+fn rows(universe: &Universe) -> Vec<String> {
+    universe
+        .get_cells()
+        .chunks(universe.width() as usize)
+        .map(|row| {
+            row.iter()
+                .map(|&cell| if cell == Cell::Alive { 'o' } else { '.' })
+                .collect::<String>()
+                .trim_end_matches('.')
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn parses_a_glider() {
+    let universe =
+        Universe::from_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+    assert_eq!(rows(&universe), vec![".o", "..o", "ooo"]);
+}
+
+#[test]
+fn counted_row_skips_leave_blank_rows() {
+    // A Gosper glider gun, then a glider three rows below it.
+    let rle = "x = 36, y = 16, rule = B3/S23
+2$24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$
+10bo5bo7bo$11bo3bo$12b2o3$bo$2bo$3o!";
+    let universe = Universe::from_rle(rle).unwrap();
+
+    assert_eq!(universe.population(), 36 + 5);
+    let rows = rows(&universe);
+    assert_eq!(rows[0], "");
+    assert_eq!(rows[1], "");
+    assert_eq!(rows[2], "........................o");
+    assert_eq!(rows[10], "............oo");
+    assert_eq!(rows[11], "");
+    assert_eq!(rows[12], "");
+    assert_eq!(rows[13], ".o");
+    assert_eq!(rows[14], "..o");
+    assert_eq!(rows[15], "ooo");
+}
+
+#[test]
+fn tolerates_standalone_bang_and_upper_case_tags() {
+    let rle = "x = 3, y = 3\nBO$2BO$\n3O\n!   \ntrailing junk after the pattern\n";
+    let universe = Universe::from_rle(rle).unwrap();
+    assert_eq!(rows(&universe), vec![".o", "..o", "ooo"]);
+}
+
+#[test]
+fn follows_the_header_rule() {
+    let highlife = Universe::from_rle("x = 3, y = 1, rule = b36/s23\n3o!").unwrap();
+    assert_eq!(highlife.rule(), "B36/S23");
+    assert_eq!(
+        Universe::from_rle("x = 1, y = 1\no!").unwrap().rule(),
+        "B3/S23"
+    );
+}
+
+#[test]
+fn rejects_bad_input() {
+    assert!(Universe::from_rle("").is_err());
+    assert!(Universe::from_rle("x = 2, y = 2\n3o!").is_err());
+    assert!(Universe::from_rle("x = 3, y = 3, rule = B39/S23\nbo!").is_err());
+    assert!(Universe::from_rle("x = 3, y = 3\nbxo!").is_err());
+}
+
+#[test]
+fn rejects_runs_that_overflow() {
+    for rle in [
+        "x = 3, y = 3\n4294967295b4294967295b!",
+        "x = 3, y = 3\n4294967295$4294967295$o!",
+        "x = 3, y = 3\n4294967295bo!",
+        "x = 3, y = 3\nb4294967295o!",
+    ] {
+        match Universe::from_rle(rle) {
+            Err(err) => assert!(
+                err.message().contains("exceeds"),
+                "{:?}: {}",
+                rle,
+                err.message()
+            ),
+            Ok(_) => panic!("{:?} should not parse", rle),
+        }
+    }
+}
+
+#[test]
+fn rejects_headers_too_large_to_allocate() {
+    for rle in ["x = 70000, y = 70000\no!", "x = 16384, y = 16384\no!"] {
+        match Universe::from_rle(rle) {
+            Err(err) => assert!(err.message().contains("limit"), "{}", err.message()),
+            Ok(_) => panic!("{:?} should not parse", rle),
+        }
+    }
+    assert!(Universe::from_rle("x = 8192, y = 8192\no!").is_ok());
+}
+
+#[test]
+fn writes_runs_and_blank_rows() {
+    let mut universe = Universe::empty(6, 6);
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2), (5, 5)]);
+    assert_eq!(
+        universe.to_rle(),
+        "x = 6, y = 6, rule = B3/S23\nbo$2bo$3o3$5bo!\n"
+    );
+    assert_eq!(
+        Universe::empty(3, 2).to_rle(),
+        "x = 3, y = 2, rule = B3/S23\n!\n"
+    );
+}
+
+#[test]
+fn long_bodies_wrap_and_round_trip() {
+    let mut universe = Universe::empty(50, 20);
+    let checker: Vec<(u32, u32)> = (0..20)
+        .flat_map(|row| (0..50).map(move |col| (row, col)))
+        .filter(|&(row, col)| (row + col) % 3 == 0)
+        .collect();
+    universe.set_cells(&checker);
+
+    let rle = universe.to_rle();
+    assert!(rle.lines().all(|line| line.len() <= 70));
+    let parsed = Universe::from_rle(&rle).unwrap();
+    assert_eq!(parsed.get_cells(), universe.get_cells());
+}
+
+#[test]
+fn metadata_round_trips_through_comments() {
+    let mut universe = Universe::empty(4, 4);
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.set_metadata("name", "Block").unwrap();
+    universe
+        .set_metadata("author", "unknown = anonymous")
+        .unwrap();
+    universe.set_metadata("rule", "B36/S23").unwrap();
+    assert_eq!(universe.get_metadata("rule").as_deref(), Some("B36/S23"));
+    assert_eq!(universe.get_metadata("missing"), None);
+
+    let rle = universe.to_rle();
+    assert!(rle.starts_with("#C author=unknown = anonymous\n#C name=Block\n"));
+    let parsed = Universe::from_rle(&rle).unwrap();
+    for key in ["name", "author", "rule"] {
+        assert_eq!(
+            parsed.get_metadata(key),
+            universe.get_metadata(key),
+            "{}",
+            key
+        );
+    }
+    assert_eq!(parsed.rule(), "B36/S23");
+
+    assert!(universe.set_metadata("a=b", "c").is_err());
+    assert!(universe.set_metadata("note", "two\nlines").is_err());
+    assert!(universe.set_metadata("rule", "B9/S").is_err());
+}
+
+#[test]
+fn writes_minimal_body_for_a_corner_pattern() {
+    let mut universe = Universe::empty(64, 64);
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    assert_eq!(
+        universe.to_rle(),
+        "x = 64, y = 64, rule = B3/S23\nbo$2bo$3o!\n"
+    );
+}
+
+#[test]
+fn keeps_leading_blank_rows() {
+    let mut universe = Universe::empty(8, 8);
+    universe.set_cells(&[(2, 3), (2, 4), (3, 3), (3, 4)]);
+    let rle = universe.to_rle();
+    assert_eq!(rle, "x = 8, y = 8, rule = B3/S23\n2$3b2o$3b2o!\n");
+    assert_eq!(
+        Universe::from_rle(&rle).unwrap().get_cells(),
+        universe.get_cells()
+    );
+}
+
+#[test]
+fn splits_a_file_of_several_patterns() {
+    let file = "#N Glider\n#C name=glider\nx = 3, y = 3\nbo$2bo$3o!\n\n\
+                #N Blinker\n#C name=blinker\nx = 5, y = 1, rule = B36/S23\n3o!\n";
+    let patterns = Universe::from_rle_multi(file).unwrap();
+    assert_eq!(patterns.len(), 2);
+
+    assert_eq!((patterns[0].width(), patterns[0].height()), (3, 3));
+    assert_eq!(rows(&patterns[0]), vec![".o", "..o", "ooo"]);
+    assert_eq!(patterns[0].get_metadata("name"), Some("glider".to_string()));
+
+    assert_eq!((patterns[1].width(), patterns[1].height()), (5, 1));
+    assert_eq!(rows(&patterns[1]), vec!["ooo"]);
+    assert_eq!(patterns[1].rule(), "B36/S23");
+    assert_eq!(
+        patterns[1].get_metadata("name"),
+        Some("blinker".to_string())
+    );
+
+    assert!(Universe::from_rle_multi("bo$2bo$3o!").is_err());
+    match Universe::from_rle_multi("x = 1, y = 1\no!\nx = 1, y = 1\n2o!\n") {
+        Err(err) => assert!(err.message().starts_with("pattern 2:")),
+        Ok(_) => panic!("the second pattern overflows its header"),
+    }
+}
+
+#[test]
+fn free_text_comments_dont_break_loading() {
+    let rle = "#C =x\n#C rule=whatever\n#C see rule=B3/S23 in header\n#C name=Glider\n\
+               x = 3, y = 3, rule = B36/S23\nbo$2bo$3o!\n";
+    let universe = Universe::from_rle(rle).unwrap();
+    assert_eq!(rows(&universe), vec![".o", "..o", "ooo"]);
+    assert_eq!(universe.rule(), "B36/S23");
+    assert_eq!(universe.get_metadata("name").as_deref(), Some("Glider"));
+    assert_eq!(universe.get_metadata("see rule"), None);
+    assert!(!universe.to_rle().contains("see rule"));
+
+    let mut universe = universe;
+    assert!(universe.set_metadata("see rule", "x").is_err());
+    assert!(universe.set_metadata("", "x").is_err());
+}
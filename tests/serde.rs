@@ -0,0 +1,51 @@
+//! serde round trips, run with `cargo test --features serde`.
+
+#![cfg(feature = "serde")]
+
+use wasm_game_of_life::{Cell, Universe};
+
+#[test]
+fn universe_round_trips_through_bincode() {
+    let mut universe = Universe::empty(13, 7);
+    universe.set_rule("B36/S23").unwrap();
+    universe.add_glider(3, 3);
+    universe.tick();
+
+    let bytes = bincode::serialize(&universe).unwrap();
+    let restored: Universe = bincode::deserialize(&bytes).unwrap();
+    assert_eq!((restored.width(), restored.height()), (13, 7));
+    assert_eq!(restored.generation(), 1);
+    assert_eq!(restored.rule(), "B36/S23");
+    assert_eq!(restored.get_cells(), universe.get_cells());
+}
+
+#[test]
+fn universe_serializes_packed_cells() {
+    let mut universe = Universe::empty(4, 2);
+    universe.set_cells(&[(0, 0), (1, 3)]);
+    assert_eq!(
+        serde_json::to_string(&universe).unwrap(),
+        r#"{"width":4,"height":2,"generation":0,"rule":"B3/S23","cells":[129]}"#
+    );
+
+    let truncated = r#"{"width":4,"height":4,"generation":0,"rule":"B3/S23","cells":[129]}"#;
+    assert!(serde_json::from_str::<Universe>(truncated).is_err());
+}
+
+#[test]
+fn oversized_dimensions_are_a_serde_error() {
+    for json in [
+        r#"{"width":70000,"height":70000,"generation":0,"rule":"B3/S23","cells":[]}"#,
+        r#"{"width":16384,"height":16384,"generation":0,"rule":"B3/S23","cells":[]}"#,
+    ] {
+        let err = serde_json::from_str::<Universe>(json).err().unwrap();
+        assert!(err.to_string().contains("limit"), "{}", err);
+    }
+}
+
+#[test]
+fn cell_round_trips() {
+    let bytes = bincode::serialize(&[Cell::Alive, Cell::Dead]).unwrap();
+    let cells: [Cell; 2] = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(cells, [Cell::Alive, Cell::Dead]);
+}
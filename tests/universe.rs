@@ -0,0 +1,1948 @@
+//! Native test suite, run with `cargo test`.
+
+use wasm_game_of_life::{BlendMode, Cell, Universe, UniverseBuilder, GLIDER};
+
+#[test]
+fn grid_and_canvas_coords_are_inverses() {
+    for &(row, col) in &[(0, 0), (3, 7), (63, 1)] {
+        let xy = Universe::grid_to_canvas(row as f64, col as f64, 5.0);
+        assert_eq!(&*xy, &[col as f64 * 5.0, row as f64 * 5.0]);
+
+        let rc = Universe::canvas_to_grid(xy[0], xy[1], 5.0);
+        assert_eq!(&*rc, &[row, col]);
+    }
+}
+
+#[test]
+fn fractional_canvas_coords_floor_to_cell() {
+    assert_eq!(&*Universe::canvas_to_grid(14.9, 5.0, 5.0), &[1, 2]);
+    assert_eq!(&*Universe::canvas_to_grid(0.2, 4.99, 5.0), &[0, 0]);
+    assert_eq!(&*Universe::canvas_to_grid(-3.0, -0.5, 5.0), &[0, 0]);
+    assert_eq!(&*Universe::grid_to_canvas(1.5, 2.5, 4.0), &[10.0, 6.0]);
+}
+
+#[test]
+fn block_accumulates_idle_generations() {
+    let mut universe = Universe::empty(8, 8);
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert_eq!(universe.generations_since_change(), 0);
+
+    for expected in 1..=5 {
+        universe.tick();
+        assert_eq!(universe.generations_since_change(), expected);
+    }
+
+    universe.toggle_cell(0, 0);
+    assert_eq!(universe.generations_since_change(), 0);
+}
+
+#[test]
+fn blinker_never_goes_idle() {
+    let mut universe = Universe::empty(8, 8);
+    universe.set_cells(&[(3, 2), (3, 3), (3, 4)]);
+
+    for _ in 0..5 {
+        universe.tick();
+        assert_eq!(universe.generations_since_change(), 0);
+    }
+}
+
+#[test]
+fn margolus_identity_table_preserves_board() {
+    let mut universe = Universe::empty(8, 8);
+    universe.set_cells(&[(0, 0), (1, 2), (3, 3), (5, 6), (7, 7)]);
+    let before = universe.get_cells().to_vec();
+
+    universe.tick_margolus_phase(0);
+    assert_eq!(universe.get_cells(), &before[..]);
+    universe.tick_margolus_phase(1);
+    assert_eq!(universe.get_cells(), &before[..]);
+}
+
+// Critters: blocks with two live cells are left alone, every other block is
+// complemented, and blocks that had three live cells are also rotated 180°.
+fn critters() -> Vec<u8> {
+    (0..16u8)
+        .map(|state| match state.count_ones() {
+            2 => state,
+            3 => {
+                let c = !state & 0xf;
+                (c & 1) << 3 | (c & 2) << 1 | (c & 4) >> 1 | (c & 8) >> 3
+            }
+            _ => !state & 0xf,
+        })
+        .collect()
+}
+
+#[test]
+fn margolus_critters_conserves_population() {
+    let alive = |u: &Universe| u.get_cells().iter().filter(|&&c| c == Cell::Alive).count();
+
+    let mut universe = Universe::empty(16, 16);
+    universe.set_margolus_rule(&critters()).unwrap();
+    universe.set_cells(&[(1, 1), (1, 2), (2, 2), (5, 9), (6, 9), (7, 10), (12, 3)]);
+    let population = alive(&universe);
+
+    for _ in 0..20 {
+        universe.tick_margolus_phase(0);
+        // Critters inverts the reading of live and dead on every half step.
+        assert_eq!(alive(&universe), 16 * 16 - population);
+        universe.tick_margolus_phase(1);
+        assert_eq!(alive(&universe), population);
+    }
+}
+
+#[test]
+fn margolus_rule_must_have_sixteen_entries() {
+    let mut universe = Universe::empty(4, 4);
+    assert!(universe.set_margolus_rule(&[0; 15]).is_err());
+}
+
+#[test]
+fn cell_defaults_to_dead() {
+    assert_eq!(Cell::default(), Cell::Dead);
+    assert_eq!(Cell::Dead.as_byte(), 0);
+    assert_eq!(Cell::Alive.as_byte(), 1);
+}
+
+#[test]
+fn blinker_births_and_deaths_per_tick() {
+    let mut universe = Universe::empty(8, 8);
+    universe.set_cells(&[(3, 2), (3, 3), (3, 4)]);
+    let before = universe.get_cells().to_vec();
+
+    assert_eq!(&*universe.birth_rate_series(5), &[2; 5]);
+    assert_eq!(&*universe.death_rate_series(5), &[2; 5]);
+    assert_eq!(universe.get_cells(), &before[..]);
+}
+
+fn live_indices(universe: &Universe) -> Vec<usize> {
+    universe
+        .get_cells()
+        .iter()
+        .enumerate()
+        .filter(|&(_, &cell)| cell == Cell::Alive)
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+#[test]
+fn glider_wraps_from_extreme_coordinates() {
+    let mut universe = Universe::empty(10, 10);
+    // i32::MIN wraps to row 2 and i32::MAX to column 7.
+    universe.add_glider(i32::MIN, i32::MAX);
+    assert_eq!(live_indices(&universe), vec![17, 28, 36, 37, 38]);
+}
+
+#[test]
+fn image_data_thresholds_luminance() {
+    let rgba = [
+        0, 0, 0, 255, // black
+        255, 255, 255, 255, // white
+        100, 100, 100, 255, // dark grey
+        200, 200, 200, 255, // light grey
+    ];
+    let universe = Universe::from_image_data(2, 2, &rgba, 128).unwrap();
+    assert_eq!(
+        universe.get_cells(),
+        &[Cell::Alive, Cell::Dead, Cell::Alive, Cell::Dead]
+    );
+
+    assert!(Universe::from_image_data(2, 2, &rgba[..12], 128).is_err());
+    assert!(Universe::from_image_data(65536, 65536, &[], 128).is_err());
+    assert!(Universe::from_image_data(70000, 70000, &rgba, 128).is_err());
+}
+
+#[test]
+fn next_state_applies_conway_rule() {
+    let universe = Universe::empty(4, 4);
+    assert_eq!(universe.next_state(Cell::Dead, 3), Cell::Alive);
+    assert_eq!(universe.next_state(Cell::Dead, 2), Cell::Dead);
+    assert_eq!(universe.next_state(Cell::Alive, 1), Cell::Dead);
+    assert_eq!(universe.next_state(Cell::Alive, 2), Cell::Alive);
+    assert_eq!(universe.next_state(Cell::Alive, 4), Cell::Dead);
+}
+
+#[test]
+fn bitset_round_trips_odd_widths() {
+    let mut universe = Universe::empty(70, 3);
+    universe.set_cells(&[(0, 0), (0, 63), (0, 64), (1, 5), (2, 69)]);
+
+    let mut buf = [0; 4];
+    universe.pack_cells_into_bitset(&mut buf).unwrap();
+    assert_eq!(buf[0], 1 << 63 | 1);
+    assert_eq!(buf[1] >> 63, 1);
+
+    let unpacked = Universe::unpack_from_bitset(&buf, 70, 3).unwrap();
+    assert_eq!(unpacked.get_cells(), universe.get_cells());
+}
+
+#[test]
+fn bitset_rejects_short_buffers() {
+    let universe = Universe::empty(70, 3);
+    assert!(universe.pack_cells_into_bitset(&mut [0; 3]).is_err());
+    assert!(Universe::unpack_from_bitset(&[0; 3], 70, 3).is_err());
+}
+
+#[test]
+fn tick_simd_matches_tick() {
+    for &size in &[8, 17, 37, 64] {
+        let mut scalar = Universe::empty(size, size);
+        scalar.add_pulsar(size as i32 / 2, size as i32 / 2);
+        scalar.add_glider(1, 1);
+        let mut simd = scalar.clone();
+
+        for _ in 0..12 {
+            scalar.tick();
+            simd.tick_simd();
+            assert_eq!(simd.get_cells(), scalar.get_cells());
+        }
+    }
+}
+
+#[test]
+fn centroid_of_empty_board_is_none() {
+    let universe = Universe::empty(16, 16);
+    assert_eq!(universe.centroid(), None);
+    assert_eq!(universe.toroidal_centroid(), None);
+}
+
+#[test]
+fn toroidal_centroid_follows_pattern_across_the_edge() {
+    // A block split across all four corners.
+    let mut universe = Universe::empty(16, 16);
+    universe.set_cells(&[(15, 15), (15, 0), (0, 15), (0, 0)]);
+
+    assert_eq!(universe.centroid(), Some((7.5, 7.5)));
+    let (row, col) = universe.toroidal_centroid().unwrap();
+    assert!((row - 15.5).abs() < 1e-9, "{}", row);
+    assert!((col - 15.5).abs() < 1e-9, "{}", col);
+}
+
+#[test]
+fn centroids_agree_away_from_the_edge() {
+    let mut universe = Universe::empty(16, 16);
+    universe.set_cells(&[(4, 4), (4, 5), (5, 4), (5, 5)]);
+
+    assert_eq!(universe.centroid(), Some((4.5, 4.5)));
+    let (row, col) = universe.toroidal_centroid().unwrap();
+    assert!((row - 4.5).abs() < 1e-9 && (col - 4.5).abs() < 1e-9);
+}
+
+fn row_string(universe: &Universe, row: u32) -> String {
+    let width = universe.width() as usize;
+    universe.get_cells()[row as usize * width..(row as usize + 1) * width]
+        .iter()
+        .map(|&cell| if cell == Cell::Alive { '#' } else { '.' })
+        .collect()
+}
+
+#[test]
+fn rule110_grows_to_the_left() {
+    let mut universe = Universe::empty(9, 6);
+    universe.add_rule110_initial_state(1, 9).unwrap();
+    universe.evolve_rule110(1, 9).unwrap();
+
+    assert_eq!(row_string(&universe, 0), ".........");
+    assert_eq!(row_string(&universe, 1), "....#....");
+    assert_eq!(row_string(&universe, 2), "...##....");
+    assert_eq!(row_string(&universe, 3), "..###....");
+    assert_eq!(row_string(&universe, 4), ".##.#....");
+}
+
+#[test]
+fn rule110_rejects_rows_off_the_board() {
+    let mut universe = Universe::empty(9, 6);
+    assert!(universe.add_rule110_initial_state(6, 9).is_err());
+    assert!(universe.evolve_rule110(6, 9).is_err());
+}
+
+#[test]
+fn rule110_rejects_an_empty_start() {
+    let mut universe = Universe::empty(9, 6);
+    assert!(universe.add_rule110_initial_state(1, 0).is_err());
+    assert_eq!(universe.population(), 0);
+
+    let mut narrow = Universe::empty(0, 6);
+    assert!(narrow.add_rule110_initial_state(1, 9).is_err());
+}
+
+#[test]
+fn history_tracks_ticks_and_undos() {
+    let mut universe = Universe::empty(8, 8);
+    universe.add_glider(3, 3);
+    let start = universe.get_cells().to_vec();
+
+    for _ in 0..5 {
+        universe.tick();
+    }
+    assert_eq!(universe.history_len(), 5);
+    assert_eq!(universe.history_position(), 0);
+
+    assert!(universe.undo());
+    assert!(universe.undo());
+    assert_eq!(universe.history_len(), 5);
+    assert_eq!(universe.history_position(), 2);
+
+    assert!(universe.redo());
+    assert_eq!(universe.history_position(), 1);
+
+    // Ticking from an undone state drops what could be redone.
+    universe.tick();
+    assert_eq!(universe.history_len(), 5);
+    assert_eq!(universe.history_position(), 0);
+    assert!(!universe.redo());
+
+    while universe.undo() {}
+    assert_eq!(universe.get_cells(), &start[..]);
+    assert_eq!(universe.history_position(), 5);
+}
+
+#[test]
+fn history_is_capped() {
+    let mut universe = Universe::empty(8, 8);
+    for _ in 0..wasm_game_of_life::HISTORY_LIMIT + 10 {
+        universe.tick();
+    }
+    assert_eq!(universe.history_len(), wasm_game_of_life::HISTORY_LIMIT);
+}
+
+#[test]
+fn webp_output_has_riff_and_vp8l_headers() {
+    let mut universe = Universe::empty(7, 5);
+    universe.add_glider(2, 2);
+    let webp = universe.save_as_webp(0x000000, 0xffffff, 3).unwrap();
+
+    assert_eq!(&webp[0..4], b"RIFF");
+    assert_eq!(&webp[8..16], b"WEBPVP8L");
+    let riff_size = u32::from_le_bytes([webp[4], webp[5], webp[6], webp[7]]);
+    assert_eq!(riff_size as usize, webp.len() - 8);
+    // VP8L signature, then the 14-bit width-1 field.
+    assert_eq!(webp[20], 0x2f);
+    assert_eq!(u16::from_le_bytes([webp[21], webp[22]]) & 0x3fff, 7 * 3 - 1);
+}
+
+#[test]
+fn webp_rejects_oversized_images() {
+    let universe = Universe::empty(64, 64);
+    assert!(universe.save_as_webp(0, 0xffffff, 0).is_err());
+    assert!(universe.save_as_webp(0, 0xffffff, 1024).is_err());
+    assert!(universe.save_as_webp(0, 0xffffff, u32::MAX).is_err());
+}
+
+#[test]
+fn tick_stats_for_a_blinker() {
+    let mut universe = Universe::empty(8, 8);
+    universe.set_cells(&[(3, 2), (3, 3), (3, 4)]);
+    let generation = universe.generation();
+
+    let stats = universe.tick_stats();
+    assert_eq!(stats.generation(), generation + 1);
+    assert_eq!(stats.population(), 3);
+    assert_eq!(stats.births(), 2);
+    assert_eq!(stats.deaths(), 2);
+    assert_eq!(stats.changed(), 4);
+}
+
+#[test]
+fn undo_and_redo_move_the_generation() {
+    let mut universe = Universe::empty(8, 8);
+    universe.tick();
+    universe.tick();
+    assert_eq!(universe.generation(), 2);
+
+    universe.undo();
+    assert_eq!(universe.generation(), 1);
+    universe.redo();
+    assert_eq!(universe.generation(), 2);
+}
+
+fn embedded(rule: u8) -> Universe {
+    let mut universe = Universe::empty(11, 4);
+    universe.set_cells(&[(0, 5)]);
+    universe.embed_1d_ca(rule, 0).unwrap();
+    universe
+}
+
+#[test]
+fn embed_1d_ca_rule_30() {
+    let universe = embedded(30);
+    assert_eq!(row_string(&universe, 1), "....###....");
+    assert_eq!(row_string(&universe, 2), "...##..#...");
+    assert_eq!(row_string(&universe, 3), "..##.####..");
+}
+
+#[test]
+fn embed_1d_ca_rule_90() {
+    let universe = embedded(90);
+    assert_eq!(row_string(&universe, 1), "....#.#....");
+    assert_eq!(row_string(&universe, 2), "...#...#...");
+    assert_eq!(row_string(&universe, 3), "..#.#.#.#..");
+}
+
+#[test]
+fn embed_1d_ca_rule_110() {
+    let universe = embedded(110);
+    assert_eq!(row_string(&universe, 1), "....##.....");
+    assert_eq!(row_string(&universe, 2), "...###.....");
+    assert_eq!(row_string(&universe, 3), "..##.#.....");
+}
+
+#[test]
+fn embed_1d_ca_wraps_the_row() {
+    let mut universe = Universe::empty(5, 2);
+    universe.set_cells(&[(0, 0)]);
+    universe.embed_1d_ca(90, 0).unwrap();
+    assert_eq!(row_string(&universe, 1), ".#..#");
+}
+
+#[test]
+fn config_json_round_trips() {
+    let source = Universe::empty(40, 30);
+    let json = source.config_to_json();
+    assert_eq!(
+        json,
+        r#"{"width":40,"height":30,"rule":"B3/S23","boundary":"toroidal","neighborhood":"moore"}"#
+    );
+
+    let mut target = Universe::empty(8, 8);
+    target.config_from_json(&json).unwrap();
+    assert_eq!(target.width(), 40);
+    assert_eq!(target.height(), 30);
+    assert_eq!(target.get_cells().len(), 40 * 30);
+    assert_eq!(target.config_to_json(), json);
+}
+
+#[test]
+fn config_json_rejects_unsupported_settings() {
+    let mut universe = Universe::empty(8, 8);
+    let json =
+        r#"{"width":8,"height":8,"rule":"B3/S23","boundary":"klein","neighborhood":"moore"}"#;
+    assert!(universe.config_from_json(json).is_err());
+    let json =
+        r#"{"width":9,"height":8,"rule":"B39/S23","boundary":"toroidal","neighborhood":"moore"}"#;
+    assert!(universe.config_from_json(json).is_err());
+    assert!(universe.config_from_json("{}").is_err());
+    assert_eq!(universe.width(), 8);
+}
+
+#[test]
+fn config_json_rejects_impossible_sizes() {
+    let mut universe = Universe::empty(8, 8);
+    universe.set_rule("B36/S23").unwrap();
+    for (width, height) in [(70000, 70000), (16384, 16384), (0, 8), (8, 0)] {
+        let json = format!(
+            r#"{{"width":{},"height":{},"rule":"B3/S23","boundary":"toroidal","neighborhood":"moore"}}"#,
+            width, height
+        );
+        assert!(universe.config_from_json(&json).is_err(), "{}", json);
+        assert_eq!((universe.width(), universe.height()), (8, 8));
+        assert_eq!(universe.rule(), "B36/S23");
+    }
+
+    // Legal overall even though the new width times the old height isn't.
+    let mut tall = Universe::empty(1, 70000);
+    let json = r#"{"width":70000,"height":1,"rule":"B3/S23","boundary":"toroidal","neighborhood":"moore"}"#;
+    tall.config_from_json(json).unwrap();
+    assert_eq!(tall.get_cells().len(), 70000);
+}
+
+#[test]
+fn config_json_carries_the_rule() {
+    let mut source = Universe::empty(8, 8);
+    source.set_rule("B36/S23").unwrap();
+    let mut target = Universe::empty(8, 8);
+    target.config_from_json(&source.config_to_json()).unwrap();
+    assert_eq!(target.rule(), "B36/S23");
+}
+
+#[test]
+fn rules_parse_both_notations() {
+    let mut universe = Universe::empty(8, 8);
+    assert_eq!(universe.rule(), "B3/S23");
+    universe.set_rule("b63/s32").unwrap();
+    assert_eq!(universe.rule(), "B36/S23");
+    universe.set_rule("23/36").unwrap();
+    assert_eq!(universe.rule(), "B36/S23");
+    universe.set_rule("B2/S").unwrap();
+    assert_eq!(universe.rule(), "B2/S");
+
+    for bad in ["", "B3", "B3/23", "B9/S23", "B3x/S23", "B03/S23"] {
+        assert!(universe.set_rule(bad).is_err(), "{:?}", bad);
+    }
+    assert_eq!(universe.rule(), "B2/S");
+}
+
+#[test]
+fn push_and_pop_rule_restore_the_original() {
+    let mut universe = Universe::empty(8, 8);
+    universe.push_rule("B36/S23").unwrap();
+    assert_eq!(universe.rule(), "B36/S23");
+    assert_eq!(universe.next_state(Cell::Dead, 6), Cell::Alive);
+    universe.tick();
+
+    assert!(universe.push_rule("B3/S").is_ok());
+    assert!(universe.push_rule("nonsense").is_err());
+    assert!(universe.pop_rule());
+    assert_eq!(universe.rule(), "B36/S23");
+    assert!(universe.pop_rule());
+    assert_eq!(universe.rule(), "B3/S23");
+    assert_eq!(universe.next_state(Cell::Dead, 6), Cell::Dead);
+    assert!(!universe.pop_rule());
+}
+
+fn checkerboard(size: u32) -> Universe {
+    let mut universe = Universe::empty(size, size);
+    let cells: Vec<(u32, u32)> = (0..size)
+        .flat_map(|row| (0..size).map(move |col| (row, col)))
+        .filter(|&(row, col)| (row + col) % 2 == 0)
+        .collect();
+    universe.set_cells(&cells);
+    universe
+}
+
+#[test]
+fn autocorrelation_at_zero_lag_is_one() {
+    let mut universe = Universe::empty(16, 16);
+    universe.add_glider(4, 4);
+    assert_eq!(universe.measure_autocorrelation(0, 0), 1.0);
+    assert_eq!(checkerboard(8).measure_autocorrelation(0, 0), 1.0);
+}
+
+#[test]
+fn checkerboard_anticorrelates_at_odd_lags() {
+    let universe = checkerboard(8);
+    assert_eq!(universe.measure_autocorrelation(0, 1), -1.0);
+    assert_eq!(universe.measure_autocorrelation(-1, 0), -1.0);
+    assert_eq!(universe.measure_autocorrelation(1, 1), 1.0);
+}
+
+#[test]
+fn pad_grows_every_side_and_keeps_pattern_centred() {
+    let mut universe = Universe::empty(4, 4);
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.pad(2).unwrap();
+
+    assert_eq!(universe.width(), 8);
+    assert_eq!(universe.height(), 8);
+    assert_eq!(live_indices(&universe), vec![27, 28, 35, 36]);
+    assert_eq!(universe.centroid(), Some((3.5, 3.5)));
+}
+
+#[test]
+fn pad_handles_empty_boards_and_refuses_to_overflow() {
+    let mut universe = Universe::empty(0, 3);
+    universe.pad(1).unwrap();
+    assert_eq!((universe.width(), universe.height()), (2, 5));
+    assert_eq!(universe.get_cells().len(), 10);
+    assert_eq!(universe.population(), 0);
+
+    let mut universe = Universe::empty(0, 0);
+    universe.pad(0).unwrap();
+    assert_eq!(universe.get_cells().len(), 0);
+
+    let mut universe = Universe::empty(0, 4);
+    assert!(universe.pad(u32::MAX / 2 + 1).is_err());
+    assert!(universe.pad(u32::MAX).is_err());
+    assert_eq!((universe.width(), universe.height()), (0, 4));
+    assert_eq!(universe.count_difference_between_boundary_modes(3), 0);
+}
+
+#[test]
+fn neighbours_wrap_on_non_square_boards() {
+    // A blinker on a wide board must keep oscillating in place.
+    let mut universe = Universe::empty(12, 5);
+    universe.set_cells(&[(2, 4), (2, 5), (2, 6)]);
+    universe.tick();
+    assert_eq!(live_indices(&universe), vec![17, 29, 41]);
+    universe.tick();
+    assert_eq!(live_indices(&universe), vec![28, 29, 30]);
+}
+
+#[test]
+fn tick_hotspots_cover_every_row() {
+    let mut universe = Universe::empty(32, 24);
+    universe.add_pulsar(12, 16);
+    let before = universe.get_cells().to_vec();
+
+    let hotspots = universe.profile_tick_hotspots();
+    assert_eq!(hotspots.len(), 24);
+    assert!(hotspots.iter().all(|&us| us >= 0.0));
+    assert_eq!(universe.get_cells(), &before[..]);
+}
+
+#[test]
+fn benchmark_runs_for_about_the_requested_time() {
+    let mut universe = noise(32);
+    let start = std::time::Instant::now();
+    let cells_per_second = universe.benchmark(50.0);
+    let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+
+    assert!(cells_per_second > 0.0 && cells_per_second.is_finite());
+    assert!(elapsed >= 50.0, "stopped after {}ms", elapsed);
+    // Generous, so a loaded CI runner passes, but a loop that ignores the
+    // deadline still fails.
+    assert!(elapsed < 1050.0, "ran for {}ms", elapsed);
+    assert!(universe.generation() > 0);
+}
+
+#[test]
+fn empty_board_ticks_cheaply_and_stays_correct() {
+    let mut universe = Universe::empty(16, 16);
+    for _ in 0..1000 {
+        universe.tick();
+    }
+    assert_eq!(universe.generation(), 1000);
+    assert_eq!(universe.population(), 0);
+    assert_eq!(universe.get_cells().len(), 16 * 16);
+    assert_eq!(universe.generations_since_change(), 1000);
+
+    universe.add_glider(5, 5);
+    for _ in 0..4 {
+        universe.tick();
+    }
+    assert_eq!(live_indices(&universe), vec![86, 103, 117, 118, 119]);
+    assert_eq!(universe.generation(), 1004);
+}
+
+#[test]
+fn active_region_freezes_everything_outside() {
+    let mut universe = Universe::empty(32, 32);
+    universe.add_glider(4, 4);
+    // A blinker outside the region must never flip.
+    universe.set_cells(&[(25, 24), (25, 25), (25, 26)]);
+    universe.set_active_region(0, 0, 16, 16);
+    let outside = |u: &Universe| -> Vec<usize> {
+        live_indices(u)
+            .into_iter()
+            .filter(|&i| i / 32 >= 16 || i % 32 >= 16)
+            .collect()
+    };
+    let frozen = outside(&universe);
+
+    for _ in 0..8 {
+        universe.tick();
+        assert_eq!(outside(&universe), frozen);
+    }
+    // The glider moved two cells down and right.
+    let inside: Vec<usize> = live_indices(&universe)
+        .into_iter()
+        .filter(|&i| i / 32 < 16 && i % 32 < 16)
+        .collect();
+    assert_eq!(inside, vec![166, 199, 229, 230, 231]);
+
+    universe.set_active_region(0, 0, 32, 32);
+    universe.tick();
+    assert_ne!(outside(&universe), frozen);
+}
+
+#[test]
+fn ticks_due_keeps_to_the_target_rate() {
+    let mut universe = Universe::empty(8, 8);
+    assert_eq!(universe.ticks_due(0.0, 45.0), 0);
+
+    // Ten seconds of 60Hz frames at 45 generations per second.
+    let mut total = 0;
+    for frame in 1..=600 {
+        let due = universe.ticks_due(frame as f64 * 1000.0 / 60.0, 45.0);
+        assert!(due <= 1, "frame {} ran {} ticks", frame, due);
+        total += due;
+    }
+    assert!((449..=450).contains(&total), "{}", total);
+
+    // A slow frame catches up; a long stall is capped at a second's worth.
+    assert_eq!(universe.ticks_due(10_000.0 + 100.0, 45.0), 4);
+    assert_eq!(universe.ticks_due(70_000.0, 45.0), 45);
+    assert_eq!(universe.ticks_due(70_010.0, 0.0), 0);
+}
+
+#[test]
+fn preview_next_predicts_a_blinker_flip() {
+    let mut universe = Universe::empty(5, 5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    let before = universe.get_cells().to_vec();
+
+    let (births, deaths) = universe.preview_next();
+    assert_eq!(births, vec![7, 17]);
+    assert_eq!(deaths, vec![11, 13]);
+    assert_eq!(universe.get_cells(), &before[..]);
+    assert_eq!(universe.generation(), 0);
+
+    universe.tick();
+    assert_eq!(live_indices(&universe), vec![7, 12, 17]);
+    assert_eq!(universe.preview_next(), (vec![11, 13], vec![7, 17]));
+}
+
+#[test]
+fn state_space_follows_the_rho_path() {
+    // On a 3x3 torus every cell neighbours the other eight, so three live
+    // cells fill the board, which then starves to an empty fixed point.
+    let mut universe = Universe::empty(3, 3);
+    universe.set_cells(&[(0, 0), (0, 1), (1, 0)]);
+    assert_eq!(universe.state_space().unwrap(), vec![0b1011, 0x1ff, 0, 0]);
+    assert_eq!(universe.generation(), 0);
+
+    // Four cells make a still life.
+    universe.set_cells(&[(1, 1)]);
+    assert_eq!(universe.state_space().unwrap(), vec![0b11011, 0b11011]);
+
+    assert!(Universe::empty(6, 5).state_space().is_err());
+}
+
+#[test]
+fn churn_counts_cells_changed_by_the_last_tick() {
+    let mut block = Universe::empty(6, 6);
+    block.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert_eq!(block.churn(), 0);
+    block.tick();
+    assert_eq!(block.churn(), 0);
+
+    let mut blinker = Universe::empty(5, 5);
+    blinker.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    for _ in 0..4 {
+        blinker.tick();
+        assert_eq!(blinker.churn(), 4);
+    }
+}
+
+#[test]
+fn overlay_blends_two_boards() {
+    // Cells 0 and 1 alive on one, 1 and 2 on the other.
+    let mut a = Universe::empty(2, 2);
+    a.set_cells(&[(0, 0), (0, 1)]);
+    let mut b = Universe::empty(2, 2);
+    b.set_cells(&[(0, 1), (1, 0)]);
+
+    let blended = |mode| live_indices(&a.overlay(&b, mode).unwrap());
+    assert_eq!(blended(BlendMode::Or), vec![0, 1, 2]);
+    assert_eq!(blended(BlendMode::And), vec![1]);
+    assert_eq!(blended(BlendMode::Xor), vec![0, 2]);
+    assert_eq!(blended(BlendMode::Subtract), vec![0]);
+    assert_eq!(live_indices(&a), vec![0, 1]);
+
+    a.apply_overlay(&b, BlendMode::Xor).unwrap();
+    assert_eq!(live_indices(&a), vec![0, 2]);
+
+    assert!(a.overlay(&Universe::empty(2, 3), BlendMode::Or).is_err());
+    assert!(a
+        .apply_overlay(&Universe::empty(3, 2), BlendMode::Or)
+        .is_err());
+}
+
+#[test]
+fn status_summarises_the_board() {
+    let mut blinker = Universe::empty(5, 5);
+    blinker.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    assert_eq!(blinker.status(), "gen 0 | pop 3 | stable=false");
+    blinker.tick();
+    assert_eq!(blinker.status(), "gen 1 | pop 3 | stable=false");
+
+    let mut block = Universe::empty(6, 6);
+    block.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    block.tick();
+    block.tick();
+    assert_eq!(block.status(), "gen 2 | pop 4 | stable=true");
+}
+
+#[test]
+fn packed_cells_round_trip() {
+    let mut universe = Universe::empty(5, 3);
+    universe.set_cells(&[(0, 0), (0, 3), (1, 4), (2, 4)]);
+    // Cells 0, 3, 9 and 14, least significant bit first.
+    assert_eq!(universe.cells_packed(), vec![0b0000_1001, 0b0100_0010]);
+
+    let unpacked = Universe::from_packed(5, 3, &universe.cells_packed()).unwrap();
+    assert_eq!((unpacked.width(), unpacked.height()), (5, 3));
+    assert_eq!(unpacked.get_cells(), universe.get_cells());
+
+    assert!(Universe::from_packed(5, 3, &[0]).is_err());
+    assert!(Universe::from_packed(5, 3, &[0, 0, 0]).is_err());
+    assert!(Universe::from_packed(70000, 70000, &[]).is_err());
+}
+
+#[test]
+fn turing_tape_round_trips() {
+    let mut universe = Universe::empty(8, 4);
+    let tape = [1, 0, 1, 1, 0, 0, 1, 0];
+    universe.create_turing_tape(&tape, 2).unwrap();
+    assert_eq!(&*universe.read_turing_tape(2).unwrap(), &tape[..]);
+    assert_eq!(row_string(&universe, 2), "#.##..#.");
+
+    // A shorter tape is centred and the rest of the row cleared.
+    universe.create_turing_tape(&[1, 1, 0, 1], 2).unwrap();
+    assert_eq!(
+        &*universe.read_turing_tape(2).unwrap(),
+        &[0, 0, 1, 1, 0, 1, 0, 0]
+    );
+
+    assert!(universe.create_turing_tape(&[0; 9], 0).is_err());
+    assert!(universe.create_turing_tape(&tape, 4).is_err());
+    assert!(universe.read_turing_tape(4).is_err());
+}
+
+// A board with each cell alive with probability 1/2, from a fixed xorshift
+// sequence so the test doesn't depend on `js_sys::Math::random`.
+fn noise(size: u32) -> Universe {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut universe = Universe::empty(size, size);
+    let cells: Vec<(u32, u32)> = (0..size * size)
+        .filter(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state & 1 == 1
+        })
+        .map(|idx| (idx / size, idx % size))
+        .collect();
+    universe.set_cells(&cells);
+    universe
+}
+
+#[test]
+fn kolmogorov_estimate_ranks_order_below_noise() {
+    let packed_len = 64 * 64 / 8;
+    let empty = Universe::empty(64, 64).kolmogorov_estimate();
+    let mut full = Universe::empty(64, 64);
+    let all: Vec<(u32, u32)> = (0..64)
+        .flat_map(|row| (0..64).map(move |col| (row, col)))
+        .collect();
+    full.set_cells(&all);
+    let full = full.kolmogorov_estimate();
+    let random = noise(64).kolmogorov_estimate();
+    assert!(empty < 32 && full < 32, "{} {}", empty, full);
+    assert!(random > packed_len * 9 / 10, "{}", random);
+
+    let checkerboard = checkerboard(64).kolmogorov_estimate();
+    let mut pulsars = Universe::empty(64, 64);
+    pulsars.add_pulsar(16, 16);
+    pulsars.add_pulsar(40, 44);
+    let pulsars = pulsars.kolmogorov_estimate();
+    assert!(empty <= checkerboard, "{} {}", empty, checkerboard);
+    assert!(checkerboard < pulsars, "{} {}", checkerboard, pulsars);
+    assert!(pulsars < random, "{} {}", pulsars, random);
+}
+
+#[test]
+fn clear_border_kills_the_outer_rings() {
+    let mut universe = noise(12);
+    let inside_before: Vec<Cell> = universe.get_cells()[2 * 12..10 * 12]
+        .chunks(12)
+        .flat_map(|row| row[2..10].to_vec())
+        .collect();
+    universe.clear_border(1);
+    for i in 0..12 {
+        for &(row, col) in &[(0, i), (11, i), (i, 0), (i, 11)] {
+            assert_eq!(universe.get_cells()[row * 12 + col], Cell::Dead);
+        }
+    }
+
+    universe.clear_border(2);
+    let inside_after: Vec<Cell> = universe.get_cells()[2 * 12..10 * 12]
+        .chunks(12)
+        .flat_map(|row| row[2..10].to_vec())
+        .collect();
+    assert_eq!(inside_after, inside_before);
+    assert_eq!(
+        universe.population() as usize,
+        inside_before
+            .iter()
+            .filter(|&&cell| cell == Cell::Alive)
+            .count()
+    );
+
+    universe.clear_border(6);
+    assert_eq!(universe.population(), 0);
+}
+
+#[test]
+fn seeded_boards_remember_their_seed() {
+    let mut universe = Universe::empty(32, 32);
+    assert_eq!(universe.seed(), None);
+    universe.reset_with_seed(42);
+    assert_eq!(universe.seed(), Some(42));
+
+    let same = Universe::new_with_seed(42);
+    assert_eq!(same.seed(), Some(42));
+    let mut again = Universe::empty(64, 64);
+    again.reset_with_seed(42);
+    assert_eq!(same.get_cells(), again.get_cells());
+    assert_ne!(Universe::new_with_seed(43).get_cells(), same.get_cells());
+
+    let population = same.population();
+    assert!((1800..2300).contains(&population), "{}", population);
+}
+
+#[test]
+fn row_rule_90_draws_sierpinski() {
+    let mut universe = Universe::empty(9, 1);
+    universe.set_cells(&[(0, 4)]);
+    let mut rows = vec![row_string(&universe, 0)];
+    for _ in 0..4 {
+        universe.as_row_rule(90).unwrap();
+        rows.push(row_string(&universe, 0));
+    }
+    assert_eq!(
+        rows,
+        vec![
+            "....#....",
+            "...#.#...",
+            "..#...#..",
+            ".#.#.#.#.",
+            "#.......#"
+        ]
+    );
+    assert_eq!(universe.generation(), 4);
+    assert!(universe.undo());
+    assert_eq!(row_string(&universe, 0), ".#.#.#.#.");
+
+    assert!(Universe::empty(9, 2).as_row_rule(90).is_err());
+}
+
+#[test]
+fn local_density_of_full_and_sparse_boards() {
+    let mut full = Universe::empty(10, 10);
+    let all: Vec<(u32, u32)> = (0..10)
+        .flat_map(|row| (0..10).map(move |col| (row, col)))
+        .collect();
+    full.set_cells(&all);
+    for radius in [0, 1, 3, 7] {
+        assert_eq!(full.local_density(4, 6, radius), 1.0);
+        assert!(full.density_map(radius).iter().all(|&d| d == 1.0));
+    }
+
+    let mut single = Universe::empty(64, 64);
+    single.set_cells(&[(0, 0)]);
+    for radius in [1, 2, 5] {
+        let expected = 1.0 / ((2 * radius + 1) * (2 * radius + 1)) as f64;
+        // The square around (63, 63) wraps to reach the live corner.
+        assert!((single.local_density(63, 63, radius) - expected).abs() < 1e-12);
+        assert_eq!(single.local_density(32, 32, radius), 0.0);
+    }
+}
+
+#[test]
+fn density_map_matches_local_density() {
+    let universe = noise(13);
+    for radius in [0, 2, 4] {
+        let map = universe.density_map(radius);
+        for row in 0..13 {
+            for col in 0..13 {
+                let expected = universe.local_density(row, col, radius) as f32;
+                assert!((map[(row * 13 + col) as usize] - expected).abs() < 1e-6);
+            }
+        }
+    }
+}
+
+#[test]
+fn count_alive_in_rect_clamps_to_the_board() {
+    let mut universe = Universe::empty(10, 10);
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    universe.set_cells(&[(8, 8), (8, 9), (9, 8), (9, 9)]);
+
+    assert_eq!(universe.count_alive_in_rect(1, 1, 4, 4), 4);
+    // Only the block's right column is inside.
+    assert_eq!(universe.count_alive_in_rect(0, 3, 5, 3), 2);
+    // Running off the bottom-right corner is clamped, not wrapped.
+    assert_eq!(universe.count_alive_in_rect(9, 9, 50, 50), 1);
+    assert_eq!(universe.count_alive_in_rect(0, 0, 10, 10), 8);
+    assert_eq!(universe.count_alive_in_rect(20, 20, 5, 5), 0);
+}
+
+#[test]
+fn soups_follow_the_sha256_stream() {
+    let mut universe = Universe::empty(16, 16);
+    universe.reset_soup("k_abc123", 16).unwrap();
+    assert_eq!(universe.population(), 104);
+    assert_eq!(row_string(&universe, 0), "..#.#....#....##");
+
+    // Larger soups continue with the hash of the previous digest.
+    let mut large = Universe::empty(24, 24);
+    large.reset_soup("k_abc123", 20).unwrap();
+    assert_eq!(large.population(), 176);
+    assert_eq!(&row_string(&large, 2)[..2], "..");
+    assert_eq!(&row_string(&large, 2)[2..18], "..#.#....#....##");
+    large.reset_soup("k_abc123", 20).unwrap();
+    assert_eq!(large.population(), 176);
+
+    assert!(universe.reset_soup("k_abc123", 17).is_err());
+}
+
+#[test]
+fn builder_configures_universe_in_one_go() {
+    let universe = UniverseBuilder::new()
+        .width(40)
+        .height(30)
+        .rule("B36/S23")
+        .boundary("toroidal")
+        .pattern(10, 10, &GLIDER)
+        .build()
+        .unwrap();
+
+    assert_eq!((universe.width(), universe.height()), (40, 30));
+    assert_eq!(universe.rule(), "B36/S23");
+    assert_eq!(universe.population(), 5);
+    assert_eq!(universe.generation(), 0);
+}
+
+#[test]
+fn builder_rejects_invalid_settings() {
+    assert!(UniverseBuilder::new().width(0).build().is_err());
+    assert!(UniverseBuilder::new().rule("B0/S8").build().is_err());
+    assert!(UniverseBuilder::new().boundary("klein").build().is_err());
+}
+
+#[test]
+fn builder_rejects_sizes_too_large_to_allocate() {
+    let overflowing = UniverseBuilder::new().width(70000).height(70000);
+    assert!(overflowing.build().is_err());
+    let oversized = UniverseBuilder::new().width(16384).height(16384);
+    assert!(oversized.build().is_err());
+}
+
+#[test]
+fn builder_seed_matches_reset_with_seed() {
+    let built = UniverseBuilder::new()
+        .width(16)
+        .height(16)
+        .seed(7)
+        .build()
+        .unwrap();
+    let mut reset = Universe::empty(16, 16);
+    reset.reset_with_seed(7);
+
+    assert_eq!(built.cells_packed(), reset.cells_packed());
+    assert_eq!(built.seed(), Some(7));
+}
+
+#[test]
+fn neighbor_counts_on_three_by_three() {
+    // On a 3x3 torus every other cell is a neighbour, so a cell's count is
+    // the population minus its own state.
+    let mut universe = Universe::empty(3, 3);
+    universe.set_cells(&[(0, 0), (0, 1), (2, 2)]);
+    assert_eq!(universe.display_neighbor_counts(), "223\n333\n332\n");
+}
+
+#[test]
+fn neighbor_counts_around_a_blinker() {
+    let mut universe = Universe::empty(5, 5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    assert_eq!(
+        universe.display_neighbor_counts(),
+        "00000\n12321\n11211\n12321\n00000\n"
+    );
+}
+
+#[test]
+fn erasing_a_glider_leaves_five_dead_cells() {
+    let mut universe = Universe::empty(8, 8);
+    let everything: Vec<(u32, u32)> = (0..8)
+        .flat_map(|row| (0..8).map(move |col| (row, col)))
+        .collect();
+    universe.set_cells(&everything);
+
+    // Anchored at the corner, so the glider wraps around both edges.
+    universe.erase_pattern(0, 0, &GLIDER);
+    assert_eq!(universe.population(), 59);
+    assert_eq!(universe.get_cells()[7 * 8], Cell::Dead);
+    assert_eq!(universe.get_cells()[8 + 7], Cell::Dead);
+}
+
+fn elementary_rows(diagrams: &[u8], rule: usize, generations: usize, width: usize) -> Vec<String> {
+    diagrams[rule * generations * width..(rule + 1) * generations * width]
+        .chunks(width)
+        .map(|row| {
+            row.iter()
+                .map(|&b| if b == 1 { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn all_1d_rules_match_known_diagrams() {
+    let mut universe = Universe::empty(9, 2);
+    universe.set_cells(&[(1, 4)]);
+    let diagrams = universe.run_all_1d_rules(1, 4).unwrap();
+    assert_eq!(diagrams.len(), 256 * 4 * 9);
+
+    let rows = |rule| elementary_rows(&diagrams, rule, 4, 9);
+    assert_eq!(
+        rows(30),
+        ["....#....", "...###...", "..##..#..", ".##.####."]
+    );
+    assert_eq!(
+        rows(90),
+        ["....#....", "...#.#...", "..#...#..", ".#.#.#.#."]
+    );
+    assert_eq!(
+        rows(110),
+        ["....#....", "...##....", "..###....", ".##.#...."]
+    );
+    assert_eq!(
+        rows(150),
+        ["....#....", "...###...", "..#.#.#..", ".##.#.##."]
+    );
+    // Rule 0 kills everything after the initial row.
+    assert_eq!(rows(0)[1], ".........");
+}
+
+#[test]
+fn rule_30_centre_column_is_known_sequence() {
+    let mut universe = Universe::empty(41, 1);
+    universe.set_cells(&[(0, 20)]);
+    let diagrams = universe.run_all_1d_rules(0, 16).unwrap();
+    let centre: Vec<u8> = elementary_rows(&diagrams, 30, 16, 41)
+        .iter()
+        .map(|row| (row.as_bytes()[20] == b'#') as u8)
+        .collect();
+    assert_eq!(centre, [1, 1, 0, 1, 1, 1, 0, 0, 1, 1, 0, 0, 0, 1, 0, 1]);
+    assert!(universe.run_all_1d_rules(1, 16).is_err());
+}
+
+#[test]
+fn run_until_population_on_a_dying_diagonal() {
+    // A diagonal of three cells goes 3 -> 1 -> 0.
+    let dying = || {
+        let mut universe = Universe::empty(8, 8);
+        universe.set_cells(&[(2, 2), (3, 3), (4, 4)]);
+        universe
+    };
+
+    let mut universe = dying();
+    assert_eq!(universe.run_until_population(3, 10), Some(0));
+    assert_eq!(universe.run_until_population(1, 10), Some(1));
+    assert_eq!(universe.run_until_population(0, 10), Some(2));
+    assert_eq!(universe.population(), 0);
+
+    let mut universe = dying();
+    assert_eq!(universe.run_until_population(2, 10), None);
+    assert_eq!(dying().run_until_population(0, 1), None);
+}
+
+#[test]
+fn csr_reconstructs_live_cells() {
+    let mut universe = Universe::empty(7, 5);
+    universe.set_cells(&[(0, 6), (2, 0), (2, 3), (2, 4), (4, 1)]);
+
+    let offsets = universe.csr_row_offsets();
+    let columns = universe.csr_column_indices();
+    assert_eq!(&offsets[..], &[0, 1, 1, 4, 4, 5]);
+    assert_eq!(&columns[..], &[6, 0, 3, 4, 1]);
+
+    let rebuilt: Vec<usize> = (0..5)
+        .flat_map(|row| {
+            let columns = &columns;
+            (offsets[row]..offsets[row + 1]).map(move |i| row * 7 + columns[i as usize] as usize)
+        })
+        .collect();
+    assert_eq!(rebuilt, live_indices(&universe));
+    assert_eq!(universe.to_csr(), (offsets, columns));
+}
+
+#[test]
+fn resizing_keeps_the_generation() {
+    let mut universe = Universe::empty(8, 8);
+    universe.set_cells(&[(1, 1), (1, 2), (1, 3)]);
+    for _ in 0..3 {
+        universe.tick();
+    }
+
+    universe.set_width(10);
+    assert_eq!(universe.generation(), 3);
+    assert_eq!(universe.population(), 0);
+    universe.set_height(12);
+    assert_eq!(universe.generation(), 3);
+
+    universe.tick();
+    assert_eq!(universe.generation(), 4);
+}
+
+#[test]
+fn resize_keeps_cells_that_fit_and_the_generation() {
+    let mut universe = Universe::empty(6, 6);
+    universe.set_cells(&[(0, 0), (2, 3), (5, 5)]);
+    universe.tick();
+    let generation = universe.generation();
+    universe.set_cells(&[(0, 0), (2, 3), (5, 5)]);
+
+    universe.resize(4, 8);
+    assert_eq!((universe.width(), universe.height()), (4, 8));
+    assert_eq!(live_indices(&universe), [0, 2 * 4 + 3]);
+    assert_eq!(universe.generation(), generation);
+    assert!(!universe.undo());
+
+    universe.resize(6, 3);
+    assert_eq!(live_indices(&universe), [0, 2 * 6 + 3]);
+    assert_eq!(universe.generation(), generation);
+}
+
+#[test]
+fn replayed_deltas_match_the_recorded_run() {
+    let mut universe = noise(32);
+    let start = universe.clone();
+
+    let mut boards = Vec::new();
+    let mut reference = start.clone();
+    for _ in 0..20 {
+        reference.tick();
+        boards.push(reference.get_cells().to_vec());
+    }
+
+    let deltas = universe.record_deltas(20);
+    assert_eq!(deltas.len(), 20);
+    assert_eq!(universe.get_cells(), &boards[19][..]);
+
+    let replayed = start.replay_deltas(&deltas).unwrap();
+    for (replayed, board) in replayed.iter().zip(&boards) {
+        assert_eq!(replayed.get_cells(), &board[..]);
+    }
+    assert_eq!(replayed[19].generation(), 20);
+    assert!(start.replay_deltas(&[vec![32 * 32]]).is_err());
+}
+
+#[test]
+fn webgl_texture_rows_are_padded_to_the_stride() {
+    let mut universe = Universe::empty(3, 2);
+    universe.set_cells(&[(0, 1), (1, 2)]);
+
+    // Rows of 3 cells * 2 px * 4 bytes = 24 bytes, padded to 32.
+    let texture = universe.export_webgl_texture(2, 16).unwrap();
+    assert_eq!(texture.len(), 32 * 4);
+
+    let pixel = |row: usize, col: usize| &texture[row * 32 + col * 4..row * 32 + col * 4 + 4];
+    assert_eq!(pixel(0, 0), [0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(pixel(0, 2), [0x00, 0x00, 0x00, 0xff]);
+    assert_eq!(pixel(1, 3), [0x00, 0x00, 0x00, 0xff]);
+    assert_eq!(pixel(2, 3), [0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(pixel(3, 5), [0x00, 0x00, 0x00, 0xff]);
+    assert!(texture[24..32].iter().all(|&byte| byte == 0));
+
+    // Already aligned rows get no padding.
+    assert_eq!(
+        universe.export_webgl_texture(1, 4).unwrap().len(),
+        3 * 4 * 2
+    );
+    assert!(universe.export_webgl_texture(1, 0).is_err());
+
+    // Sizes are worked out without wrapping, and boards with no columns
+    // give an empty texture.
+    let wide = Universe::empty(u32::MAX, 0);
+    assert!(wide.export_webgl_texture(u32::MAX, 4).is_err());
+    let flat = Universe::empty(1 << 20, 0);
+    assert!(flat.export_webgl_texture(1, 4).unwrap().is_empty());
+    let narrow = Universe::empty(0, 5);
+    assert!(narrow.export_webgl_texture(3, 4).unwrap().is_empty());
+}
+
+#[test]
+fn neighbor_breakdown_names_each_direction() {
+    let mut universe = Universe::empty(5, 5);
+    // North-west, east and south of (2, 2), and (2, 2) itself.
+    universe.set_cells(&[(1, 1), (2, 3), (3, 2), (2, 2)]);
+    assert_eq!(
+        universe.neighbor_breakdown(2, 2),
+        [true, false, false, false, true, false, true, false]
+    );
+
+    // At the corner the neighbours wrap: (4, 4) is north-west of (0, 0).
+    let mut corner = Universe::empty(5, 5);
+    corner.set_cells(&[(4, 4), (0, 1)]);
+    assert_eq!(
+        corner.neighbor_breakdown(0, 0),
+        [true, false, false, false, true, false, false, false]
+    );
+}
+
+#[test]
+fn tick_custom_simple_b3_s23_matches_tick() {
+    let mut conway = noise(24);
+    let mut custom = conway.clone();
+    for _ in 0..10 {
+        conway.tick();
+        custom.tick_custom_simple(3, 2, 3).unwrap();
+        assert_eq!(custom.get_cells(), conway.get_cells());
+    }
+    assert_eq!(custom.generation(), 10);
+    assert_eq!(custom.rule(), "B3/S23");
+    assert!(custom.undo());
+}
+
+#[test]
+fn tick_custom_simple_uses_its_own_rule_once() {
+    // Under B3/S1 a blinker's ends survive with one neighbour each.
+    let mut universe = Universe::empty(5, 5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.tick_custom_simple(3, 1, 1).unwrap();
+    assert_eq!(live_indices(&universe), [7, 2 * 5 + 1, 2 * 5 + 3, 17]);
+    assert!(universe.tick_custom_simple(0, 2, 3).is_err());
+}
+
+#[test]
+fn distance_counts_differing_cells() {
+    let board = noise(16);
+    assert_eq!(board.distance_to(&board.clone()).unwrap(), 0);
+
+    let mut edited = board.clone();
+    edited.toggle_cell(3, 4);
+    assert_eq!(board.distance_to(&edited).unwrap(), 1);
+    assert_eq!(edited.distance_to(&board).unwrap(), 1);
+
+    assert!(board.distance_to(&Universe::empty(16, 8)).is_err());
+}
+
+#[test]
+fn age_colors_run_from_young_to_old() {
+    let mut universe = Universe::empty(4, 1);
+    universe.set_cells(&[(0, 0), (0, 1), (0, 2)]);
+    let ages = [0, 5, 20, 7];
+
+    let pixels = universe
+        .render_age_colored(&ages, 0x00_20_ff, 0xff_a0_00, 10)
+        .unwrap();
+    assert_eq!(pixels.len(), 16);
+    assert_eq!(&pixels[0..4], [0x00, 0x20, 0xff, 0xff]);
+    assert_eq!(&pixels[4..8], [0x80, 0x60, 0x80, 0xff]);
+    assert_eq!(&pixels[8..12], [0xff, 0xa0, 0x00, 0xff]);
+    // Dead cells are background whatever their age.
+    assert_eq!(&pixels[12..16], [0xff, 0xff, 0xff, 0xff]);
+
+    assert!(universe.render_age_colored(&ages[..3], 0, 0, 10).is_err());
+}
+
+#[test]
+fn bordered_render_frames_the_grid() {
+    let mut universe = Universe::empty(3, 2);
+    universe.set_cells(&[(0, 1), (1, 2)]);
+    let text = universe.render_bordered('#');
+
+    assert_eq!(text, "#####\n#◻◼◻#\n#◻◻◼#\n#####\n");
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), universe.height() as usize + 2);
+    assert!(lines
+        .iter()
+        .all(|line| line.chars().count() == universe.width() as usize + 2));
+}
+
+#[test]
+fn checkerboard_spectrum_peaks_at_nyquist() {
+    let spectrum = checkerboard(8).spatial_power_spectrum(8);
+    assert_eq!(spectrum.len(), 64);
+
+    let peak = 4 * 8 + 4;
+    assert!((spectrum[peak] - 0.5).abs() < 1e-6, "{}", spectrum[peak]);
+    for (i, &magnitude) in spectrum.iter().enumerate() {
+        if i != peak {
+            assert!(magnitude < 1e-6, "{} at {}", magnitude, i);
+        }
+    }
+}
+
+#[test]
+fn stripes_peak_at_their_own_frequency() {
+    // Vertical stripes four columns wide repeat every 8 columns, which is
+    // one cycle across an 8-column board.
+    let mut universe = Universe::empty(8, 8);
+    let stripes: Vec<(u32, u32)> = (0..8)
+        .flat_map(|row| (0..4).map(move |col| (row, col)))
+        .collect();
+    universe.set_cells(&stripes);
+    let spectrum = universe.spatial_power_spectrum(8);
+
+    let strongest = (0..64)
+        .max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b]))
+        .unwrap();
+    assert!(strongest == 1 || strongest == 7, "{}", strongest);
+    assert_eq!(spectrum[0], 0.0);
+    assert!(Universe::empty(4, 4).spatial_power_spectrum(0).is_empty());
+}
+
+#[test]
+fn minimap_counts_cells_per_block() {
+    let mut universe = Universe::empty(64, 64);
+    // A block inside the 8x8 block at minimap (2, 5), and a glider.
+    universe.set_cells(&[(20, 44), (20, 45), (21, 44), (21, 45)]);
+    universe.add_glider(4, 4);
+
+    let minimap = universe.minimap(8, 8).unwrap();
+    assert_eq!(minimap.len(), 64);
+    assert_eq!(minimap[2 * 8 + 5], 4);
+    assert_eq!(minimap[0], 5);
+    assert_eq!(minimap.iter().map(|&count| count as u32).sum::<u32>(), 9);
+
+    // Uneven splits still cover the whole board.
+    let uneven = universe.minimap(3, 5).unwrap();
+    assert_eq!(uneven.iter().map(|&count| count as u32).sum::<u32>(), 9);
+}
+
+#[test]
+fn minimap_saturates_at_255() {
+    let mut universe = Universe::empty(20, 20);
+    let all: Vec<(u32, u32)> = (0..20)
+        .flat_map(|row| (0..20).map(move |col| (row, col)))
+        .collect();
+    universe.set_cells(&all);
+    assert_eq!(universe.minimap(1, 1).unwrap(), [255]);
+    assert_eq!(universe.minimap(2, 2).unwrap(), [100; 4]);
+}
+
+#[test]
+fn minimap_rejects_sizes_too_large_to_allocate() {
+    let universe = Universe::empty(8, 8);
+    assert!(universe.minimap(70000, 70000).is_err());
+    assert!(universe.minimap(16384, 16384).is_err());
+}
+
+#[test]
+fn tick_n_or_stable_stops_once_nothing_changes() {
+    let mut block = Universe::empty(8, 8);
+    block.set_cells(&[(3, 3), (3, 4), (4, 3), (4, 4)]);
+    assert_eq!(block.tick_n_or_stable(50), 1);
+    assert_eq!(block.generation(), 1);
+
+    let mut glider = Universe::empty(64, 64);
+    glider.add_glider(10, 10);
+    assert_eq!(glider.tick_n_or_stable(50), 50);
+    assert_eq!(glider.generation(), 50);
+
+    // A diagonal pair dies out on the first tick and stays empty.
+    let mut dying = Universe::empty(8, 8);
+    dying.set_cells(&[(1, 1), (2, 2)]);
+    assert_eq!(dying.tick_n_or_stable(50), 2);
+    assert_eq!(dying.tick_n_or_stable(0), 0);
+}
+
+// The board, its reported size and its population all agree, and so does
+// every board kept for undo and redo.
+fn assert_invariants(universe: &Universe, after: &str) {
+    let cells = universe.width() as usize * universe.height() as usize;
+    assert_eq!(universe.get_cells().len(), cells, "after {}", after);
+    let packed = Universe::from_packed(
+        universe.width(),
+        universe.height(),
+        &universe.cells_packed(),
+    )
+    .unwrap_or_else(|err| panic!("after {}: {}", after, err.message()));
+    assert_eq!(packed.get_cells(), universe.get_cells(), "after {}", after);
+
+    // The births and deaths a tick reports must account for the change in
+    // population.
+    let mut ticked = universe.clone();
+    let before = ticked.population();
+    let stats = ticked.tick_stats();
+    assert_eq!(
+        before + stats.births() - stats.deaths(),
+        ticked.population(),
+        "after {}",
+        after
+    );
+
+    let mut rewound = universe.clone();
+    let mut undone = 0;
+    while rewound.undo() {
+        assert_eq!(rewound.get_cells().len(), cells, "after {}", after);
+        undone += 1;
+    }
+    let mut redone = 0;
+    while rewound.redo() {
+        assert_eq!(rewound.get_cells().len(), cells, "after {}", after);
+        redone += 1;
+    }
+    for _ in undone..redone {
+        assert!(rewound.undo(), "after {}", after);
+    }
+    assert_eq!(rewound.get_cells(), universe.get_cells(), "after {}", after);
+    assert_eq!(
+        rewound.generation(),
+        universe.generation(),
+        "after {}",
+        after
+    );
+}
+
+type Mutator = Box<dyn Fn(&mut Universe)>;
+
+#[test]
+fn every_mutator_keeps_the_invariants() {
+    let mut universe = noise(16);
+    let steps: Vec<(&str, Mutator)> = vec![
+        ("tick", Box::new(|u| u.tick())),
+        ("tick_simd", Box::new(|u| u.tick_simd())),
+        (
+            "tick_stats",
+            Box::new(|u| {
+                let _ = u.tick_stats();
+            }),
+        ),
+        (
+            "tick_custom_simple",
+            Box::new(|u| u.tick_custom_simple(3, 2, 3).unwrap()),
+        ),
+        (
+            "tick_n_or_stable",
+            Box::new(|u| {
+                let _ = u.tick_n_or_stable(3);
+            }),
+        ),
+        (
+            "undo",
+            Box::new(|u| {
+                let _ = u.undo();
+            }),
+        ),
+        (
+            "redo",
+            Box::new(|u| {
+                let _ = u.redo();
+            }),
+        ),
+        ("set_width", Box::new(|u| u.set_width(20))),
+        ("reset_with_seed", Box::new(|u| u.reset_with_seed(5))),
+        ("tick", Box::new(|u| u.tick())),
+        ("set_height", Box::new(|u| u.set_height(12))),
+        ("add_glider", Box::new(|u| u.add_glider(3, 3))),
+        ("tick", Box::new(|u| u.tick())),
+        ("resize", Box::new(|u| u.resize(9, 14))),
+        ("pad", Box::new(|u| u.pad(3).unwrap())),
+        ("tick", Box::new(|u| u.tick())),
+        ("toggle_cell", Box::new(|u| u.toggle_cell(1, 1))),
+        (
+            "erase_pattern",
+            Box::new(|u| u.erase_pattern(3, 3, &GLIDER)),
+        ),
+        (
+            "set_active_region",
+            Box::new(|u| u.set_active_region(2, 2, 6, 6)),
+        ),
+        ("tick", Box::new(|u| u.tick())),
+        ("clear_border", Box::new(|u| u.clear_border(1))),
+        ("kill", Box::new(|u| u.kill())),
+        ("reset_soup", Box::new(|u| u.reset_soup("inv", 8).unwrap())),
+        (
+            "apply_overlay",
+            Box::new(|u| {
+                let other = u.clone();
+                u.apply_overlay(&other, BlendMode::Xor).unwrap()
+            }),
+        ),
+        (
+            "config_from_json",
+            Box::new(|u| {
+                u.config_from_json(
+                r#"{"width":7,"height":5,"rule":"B36/S23","boundary":"toroidal","neighborhood":"moore"}"#,
+            )
+            .unwrap()
+            }),
+        ),
+        (
+            "create_turing_tape",
+            Box::new(|u| u.create_turing_tape(&[1, 0, 1], 2).unwrap()),
+        ),
+        ("tick", Box::new(|u| u.tick())),
+    ];
+
+    for (name, step) in &steps {
+        step(&mut universe);
+        assert_invariants(&universe, name);
+    }
+    assert_eq!((universe.width(), universe.height()), (7, 5));
+}
+
+#[test]
+fn coordinate_arrays_round_trip_through_alive_cells_flat() {
+    let original = noise(9);
+    let flat = original.alive_cells_flat();
+    assert_eq!(flat.len(), 2 * original.population() as usize);
+    let rows: Vec<u32> = flat.iter().step_by(2).cloned().collect();
+    let cols: Vec<u32> = flat.iter().skip(1).step_by(2).cloned().collect();
+
+    let mut copy = Universe::empty(9, 9);
+    copy.add_from_coordinate_arrays(&rows, &cols).unwrap();
+    assert_eq!(copy.get_cells(), original.get_cells());
+
+    assert!(copy.add_from_coordinate_arrays(&[1, 2], &[1]).is_err());
+    copy.kill();
+    assert!(copy.add_from_coordinate_arrays(&[1, 9], &[1, 1]).is_err());
+    assert_eq!(copy.population(), 0);
+}
+
+#[test]
+fn paint_drag_fills_the_gaps_between_mouse_events() {
+    let mut universe = Universe::empty(12, 12);
+    universe.paint_drag(&[5.0, 5.0, 95.0, 95.0], 10.0, true);
+    let diagonal: Vec<usize> = (0..10).map(|i| i * 12 + i).collect();
+    assert_eq!(live_indices(&universe), diagonal);
+
+    // A shallow stroke steps down one row at a time with no gaps, and the
+    // part past the right edge is dropped.
+    universe.kill();
+    universe.paint_drag(&[0.0, 0.0, 200.0, 40.0, 200.0], 10.0, true);
+    let painted = live_indices(&universe);
+    assert_eq!(painted.len(), 12);
+    for pair in painted.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert_eq!(b % 12, a % 12 + 1);
+        assert!(b / 12 - a / 12 <= 1);
+    }
+
+    universe.paint_drag(&[0.0, 0.0, 200.0, 40.0], 10.0, false);
+    assert_eq!(universe.population(), 0);
+}
+
+#[test]
+fn paint_drag_ignores_bad_sizes_and_far_off_points() {
+    let mut universe = Universe::empty(12, 12);
+    for cell_size in [0.0, -4.0, f32::NAN, f32::INFINITY] {
+        universe.paint_drag(&[5.0, 5.0, 95.0, 95.0], cell_size, true);
+        assert_eq!(universe.population(), 0);
+    }
+    universe.paint_drag(&[f32::NAN, 5.0], 10.0, true);
+    assert_eq!(universe.population(), 0);
+
+    // A stroke from far off one corner to far off the other only walks the
+    // board's own diagonal.
+    universe.paint_drag(&[-1.0e8, -1.0e8, 1.0e8, 1.0e8], 10.0, true);
+    let diagonal: Vec<usize> = (0..12).map(|i| i * 12 + i).collect();
+    assert_eq!(live_indices(&universe), diagonal);
+    // Even at the limits of `f32` the walk stays on the board.
+    universe.paint_drag(&[-3.0e38, -3.0e38, 3.0e38, 3.0e38], 1.0e-30, true);
+    assert!(universe.population() <= 24);
+
+    // One that misses the board entirely paints nothing.
+    universe.kill();
+    universe.paint_drag(&[-50.0, 500.0, 500.0, -50.0], 1.0, true);
+    assert_eq!(universe.population(), 0);
+}
+
+#[test]
+fn paint_drag_clips_strokes_across_a_corner_at_an_angle() {
+    // Clipping these can land an end a hair below zero, which must still
+    // round onto the board's first row or column.
+    let mut universe = Universe::empty(13, 11);
+    universe.paint_drag(&[31.0, -12.0, -3.0, 2.5], 1.0, true);
+    universe.paint_drag(&[-40.5, -20.5, 49.5, 28.0], 1.0, true);
+    assert!(universe.population() > 0);
+}
+
+#[test]
+fn reset_with_draws_a_circle() {
+    let mut universe = noise(21);
+    universe.reset_with(|row, col| {
+        let (dy, dx) = (row as i32 - 10, col as i32 - 10);
+        dy * dy + dx * dx <= 25
+    });
+
+    let cell = |row, col| universe.get_cells()[(row * 21 + col) as usize];
+    assert_eq!(cell(10, 10), Cell::Alive);
+    assert_eq!(cell(10, 15), Cell::Alive);
+    assert_eq!(cell(13, 14), Cell::Alive);
+    assert_eq!(cell(10, 16), Cell::Dead);
+    assert_eq!(cell(14, 14), Cell::Dead);
+    assert_eq!(cell(0, 0), Cell::Dead);
+    // Lattice points within radius 5.
+    assert_eq!(universe.population(), 81);
+}
+
+#[test]
+fn rows_round_trip_through_bitmasks() {
+    let mut universe = Universe::empty(64, 3);
+    universe.set_row_from_bitmask(1, 0x8000_0000_dead_beef);
+    assert_eq!(universe.get_row_as_bitmask(1), 0x8000_0000_dead_beef);
+    assert_eq!(universe.get_cells()[64], Cell::Alive);
+    assert_eq!(universe.get_cells()[65], Cell::Dead);
+    assert_eq!(universe.get_cells()[127], Cell::Alive);
+    assert_eq!(universe.population(), 1 + 0xdead_beefu64.count_ones());
+
+    let mut wide = Universe::empty(128, 2);
+    let masks = [0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210];
+    wide.set_row_from_bitmasks(1, &masks).unwrap();
+    assert_eq!(&*wide.get_row_as_bitmasks(1), &masks);
+    assert_eq!(wide.get_row_as_bitmask(1), masks[0]);
+    assert_eq!(wide.get_row_as_bitmask(0), 0);
+    assert!(wide.set_row_from_bitmasks(1, &masks[..1]).is_err());
+
+    // Bits past the end of a 70-cell row are dropped.
+    let mut odd = Universe::empty(70, 2);
+    odd.set_row_from_bitmasks(0, &[u64::MAX, u64::MAX]).unwrap();
+    assert_eq!(
+        &*odd.get_row_as_bitmasks(0),
+        &[u64::MAX, 0xfc00_0000_0000_0000]
+    );
+    assert_eq!(odd.population(), 70);
+    odd.set_row_from_bitmask(0, 0);
+    assert_eq!(&*odd.get_row_as_bitmasks(0), &[0, 0xfc00_0000_0000_0000]);
+
+    let mut narrow = Universe::empty(5, 1);
+    narrow.set_row_from_bitmask(0, u64::MAX);
+    assert_eq!(narrow.get_row_as_bitmask(0), 0xf800_0000_0000_0000);
+}
+
+#[test]
+fn conway_spellings_all_normalize() {
+    let mut universe = Universe::empty(4, 4);
+    assert!(universe.is_conway());
+    for spelling in &["B3/S23", "b3/s23", "B3/S32", "b3/S32", " 23/3 ", "32/3"] {
+        universe.set_rule("B36/S23").unwrap();
+        assert!(!universe.is_conway());
+        universe.set_rule(spelling).unwrap();
+        assert!(universe.is_conway(), "{}", spelling);
+        assert_eq!(universe.rule(), "B3/S23");
+    }
+}
+
+// Live cells in each column.
+fn column_populations(universe: &Universe) -> Vec<u32> {
+    (0..universe.width() as usize)
+        .map(|col| {
+            universe
+                .get_cells()
+                .iter()
+                .skip(col)
+                .step_by(universe.width() as usize)
+                .filter(|&&cell| cell == Cell::Alive)
+                .count() as u32
+        })
+        .collect()
+}
+
+#[test]
+fn striped_boards_fill_every_stripe_at_the_density() {
+    let mut universe = Universe::empty(120, 100);
+    let widths = universe.randomize_striped_with_seed(4, 8, 0.3, 7).unwrap();
+    assert_eq!(widths.iter().sum::<u32>(), 120);
+    assert!(widths.len() > 1);
+    for &width in &widths[..widths.len() - 1] {
+        assert!((4..=8).contains(&width), "{:?}", widths);
+    }
+    assert!(*widths.last().unwrap() <= 8);
+
+    let columns = column_populations(&universe);
+    let mut col = 0;
+    for &width in widths.iter() {
+        let stripe = &columns[col..col + width as usize];
+        let density = stripe.iter().sum::<u32>() as f64 / (width * 100) as f64;
+        assert!(
+            (density - 0.3).abs() < 0.1,
+            "stripe at column {} has density {}",
+            col,
+            density
+        );
+        col += width as usize;
+    }
+
+    // A full density leaves no stripe empty.
+    universe.randomize_striped_with_seed(4, 8, 1.0, 7).unwrap();
+    assert_eq!(universe.population(), 120 * 100);
+
+    let mut again = Universe::empty(120, 100);
+    again.randomize_striped_with_seed(4, 8, 1.0, 7).unwrap();
+    assert_eq!(again.get_cells(), universe.get_cells());
+    universe.randomize_striped_with_seed(4, 8, 0.3, 7).unwrap();
+    again.randomize_striped_with_seed(4, 8, 0.3, 7).unwrap();
+    assert_eq!(again.get_cells(), universe.get_cells());
+
+    assert!(universe.randomize_striped_with_seed(0, 3, 0.5, 1).is_err());
+    assert!(universe.randomize_striped_with_seed(4, 3, 0.5, 1).is_err());
+    assert!(universe.randomize_striped_with_seed(1, 3, 1.5, 1).is_err());
+}
+
+#[test]
+fn mutate_flips_a_reproducible_set_of_cells() {
+    let mut universe = noise(8);
+    let before = universe.get_cells().to_vec();
+    universe.mutate(0.0, 3);
+    assert_eq!(universe.get_cells(), &before[..]);
+
+    let mut empty = Universe::empty(8, 8);
+    empty.mutate(0.1, 3);
+    assert_eq!(live_indices(&empty), vec![3, 47]);
+    // Flipping the same cells again undoes the noise.
+    empty.mutate(0.1, 3);
+    assert_eq!(empty.population(), 0);
+
+    universe.mutate(1.0, 3);
+    assert!(universe
+        .get_cells()
+        .iter()
+        .zip(&before)
+        .all(|(after, before)| after != before));
+
+    let mut large = Universe::empty(64, 64);
+    large.mutate(0.1, 11);
+    assert!(
+        (350..470).contains(&large.population()),
+        "{}",
+        large.population()
+    );
+}
+
+// `universe` after `generations` ticks, without any seeking.
+fn ticked(universe: &Universe, generations: u32) -> Universe {
+    let mut ticked = universe.clone();
+    for _ in 0..generations {
+        ticked.tick();
+    }
+    ticked
+}
+
+#[test]
+fn seek_uses_history_and_keyframes() {
+    let start = noise(16);
+    let mut universe = start.clone();
+    universe.precompute_keyframes(10, 50).unwrap();
+    assert_eq!(universe.generation(), 0);
+
+    for _ in 0..150 {
+        universe.tick();
+    }
+    // Within the undo history.
+    assert!(universe.seek(90));
+    assert_eq!(universe.generation(), 90);
+    assert_eq!(universe.get_cells(), ticked(&start, 90).get_cells());
+    assert!(universe.seek(120));
+    assert_eq!(universe.get_cells(), ticked(&start, 120).get_cells());
+
+    // Beyond it, from the keyframe at 20.
+    assert!(universe.seek(25));
+    assert_eq!(universe.generation(), 25);
+    assert_eq!(universe.get_cells(), ticked(&start, 25).get_cells());
+    assert_eq!(universe.history_len(), 5);
+    assert!(universe.seek(3));
+    assert_eq!(universe.get_cells(), ticked(&start, 3).get_cells());
+
+    // Editing drops the keyframes.
+    universe.seek(150);
+    universe.toggle_cell(0, 0);
+    let edited = universe.get_cells().to_vec();
+    assert!(!universe.seek(10));
+    assert_eq!(universe.generation(), 150);
+    assert_eq!(universe.get_cells(), &edited[..]);
+}
+
+#[test]
+fn seek_fails_before_the_first_keyframe() {
+    let start = ticked(&noise(16), 30);
+    let mut universe = start.clone();
+    universe.precompute_keyframes(10, 50).unwrap();
+    for _ in 0..120 {
+        universe.tick();
+    }
+    let before = universe.get_cells().to_vec();
+    assert!(!universe.seek(10));
+    assert_eq!(universe.generation(), 150);
+    assert_eq!(universe.get_cells(), &before[..]);
+
+    assert!(universe.seek(45));
+    assert_eq!(universe.get_cells(), ticked(&start, 15).get_cells());
+    assert!(universe.precompute_keyframes(0, 10).is_err());
+}
+
+#[test]
+fn seek_takes_u64_generations() {
+    let start = noise(16);
+    let mut universe = start.clone();
+    // Only generation 0 is a multiple of so wide a spacing.
+    universe.precompute_keyframes(u64::MAX, 20).unwrap();
+    for _ in 0..150 {
+        universe.tick();
+    }
+
+    let before = universe.get_cells().to_vec();
+    assert!(!universe.seek(u64::from(u32::MAX) + 1));
+    assert_eq!(universe.generation(), 150);
+    assert_eq!(universe.get_cells(), &before[..]);
+
+    assert!(universe.seek(5));
+    assert_eq!(universe.get_cells(), ticked(&start, 5).get_cells());
+}
+
+#[test]
+fn neighbor_count_histogram_buckets_every_cell() {
+    let mut full = Universe::empty(6, 5);
+    full.reset_with(|_, _| true);
+    assert_eq!(
+        full.neighbor_count_histogram(),
+        [0, 0, 0, 0, 0, 0, 0, 0, 30]
+    );
+
+    assert_eq!(
+        Universe::empty(4, 4).neighbor_count_histogram(),
+        [16, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+
+    // A blinker: the two cells beside its middle see all three, and the
+    // rows two away from it see nothing.
+    let mut blinker = Universe::empty(5, 5);
+    blinker.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    let histogram = blinker.neighbor_count_histogram();
+    assert_eq!(histogram.iter().sum::<u32>(), 25);
+    assert_eq!(histogram, [10, 8, 5, 2, 0, 0, 0, 0, 0]);
+}
+
+// A 16x16 board with keyframes every 10 generations up to 50, ticked to
+// generation 150 so that generation 30 is only reachable from a keyframe.
+fn keyframed_board() -> Universe {
+    let mut universe = noise(16);
+    universe.precompute_keyframes(10, 50).unwrap();
+    for _ in 0..150 {
+        universe.tick();
+    }
+    universe
+}
+
+#[test]
+fn rule_changes_by_any_route_drop_the_keyframes() {
+    let mut by_config = keyframed_board();
+    by_config
+        .config_from_json(
+            r#"{"width":16,"height":16,"rule":"B36/S23","boundary":"toroidal","neighborhood":"moore"}"#,
+        )
+        .unwrap();
+    assert!(!by_config.seek(30));
+
+    let mut by_metadata = keyframed_board();
+    by_metadata.set_metadata("rule", "B36/S23").unwrap();
+    assert!(!by_metadata.seek(30));
+    assert_eq!(by_metadata.rule(), "B36/S23");
+
+    let mut by_set_rule = keyframed_board();
+    by_set_rule.set_rule("B36/S23").unwrap();
+    assert!(!by_set_rule.seek(30));
+}
+
+#[test]
+fn changing_the_active_region_drops_the_keyframes() {
+    let mut universe = keyframed_board();
+    universe.set_active_region(4, 4, 8, 8);
+    assert!(!universe.seek(30));
+
+    // Keyframes computed under a region are dropped when it is lifted.
+    let mut universe = noise(16);
+    universe.set_active_region(4, 4, 8, 8);
+    universe.precompute_keyframes(10, 50).unwrap();
+    for _ in 0..150 {
+        universe.tick();
+    }
+    universe.set_active_region(0, 0, 16, 16);
+    assert!(!universe.seek(30));
+}
+
+#[test]
+fn ticks_under_another_rule_drop_the_keyframes() {
+    let mut universe = noise(16);
+    universe.precompute_keyframes(10, 50).unwrap();
+    universe.tick_custom_simple(3, 1, 1).unwrap();
+    let expected = ticked(&universe, 19);
+    assert!(universe.seek(20));
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    let mut row = Universe::empty(16, 1);
+    row.set_cells(&[(0, 7)]);
+    row.precompute_keyframes(5, 20).unwrap();
+    for _ in 0..120 {
+        row.tick();
+    }
+    row.as_row_rule(90).unwrap();
+    assert!(!row.seek(10));
+}
@@ -11,3 +11,159 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+use wasm_game_of_life::{Cell, Universe};
+
+// A 3x2 PNG with a black/white checkerboard, black in the top-left corner.
+const CHECKER_PNG: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAMAAAACCAIAAAASFvFNAAAAE0lEQVR4nGNgYGD4//8/A5wCkgBKyQj40phRjQAAAABJRU5ErkJggg==";
+
+#[wasm_bindgen_test]
+async fn from_image_url_reads_pixels() {
+    let universe = Universe::from_image_url(CHECKER_PNG.to_string(), 128)
+        .await
+        .unwrap();
+
+    assert_eq!(universe.width(), 3);
+    assert_eq!(universe.height(), 2);
+    assert_eq!(
+        universe.get_cells(),
+        &[
+            Cell::Alive,
+            Cell::Dead,
+            Cell::Alive,
+            Cell::Dead,
+            Cell::Alive,
+            Cell::Dead
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn from_image_url_reports_fetch_failures() {
+    let error = Universe::from_image_url("http://127.0.0.1:1/missing.png".to_string(), 128)
+        .await
+        .err()
+        .unwrap();
+    assert!(error.message().contains("could not fetch"));
+}
+
+#[wasm_bindgen_test]
+async fn from_image_url_rejects_non_images() {
+    let error = Universe::from_image_url("data:text/plain,hello".to_string(), 128)
+        .await
+        .err()
+        .unwrap();
+    assert!(error.message().contains("not a decodable image"));
+}
+
+// `Universe::new` seeds from `Math.random`, so these only run in the browser;
+// tests/patterns.rs covers the same ground natively.
+fn empty_universe(width: u32, height: u32) -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(width);
+    universe.set_height(height);
+    universe
+}
+
+#[wasm_bindgen_test]
+fn glider_spawns_and_moves() {
+    let mut universe = empty_universe(16, 16);
+    universe.add_glider(5, 5);
+    let mut expected = Universe::empty(16, 16);
+    expected.set_cells(&[(4, 5), (5, 6), (6, 4), (6, 5), (6, 6)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    for _ in 0..4 {
+        universe.tick();
+    }
+    let mut expected = Universe::empty(16, 16);
+    expected.set_cells(&[(5, 6), (6, 7), (7, 5), (7, 6), (7, 7)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[wasm_bindgen_test]
+fn pulsar_spawns_and_oscillates() {
+    let mut universe = empty_universe(32, 32);
+    universe.add_pulsar(16, 16);
+    assert_eq!(
+        universe
+            .get_cells()
+            .iter()
+            .filter(|&&cell| cell == Cell::Alive)
+            .count(),
+        48
+    );
+
+    let start = universe.get_cells().to_vec();
+    for _ in 0..3 {
+        universe.tick();
+    }
+    assert_eq!(universe.get_cells(), &start[..]);
+}
+
+// Only exercises the SIMD kernel when built with `+simd128`.
+#[wasm_bindgen_test]
+fn tick_simd_matches_tick() {
+    for &size in &[17, 37, 64] {
+        let mut scalar = Universe::new();
+        scalar.set_width(size);
+        scalar.set_height(size);
+        scalar.reset();
+        let mut simd = scalar.clone();
+
+        for _ in 0..12 {
+            scalar.tick();
+            simd.tick_simd();
+            assert_eq!(simd.get_cells(), scalar.get_cells());
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn js_rule_reproduces_conway() {
+    let birth = js_sys::Function::new_with_args("n", "return n === 3");
+    let survival = js_sys::Function::new_with_args("n", "return n === 2 || n === 3");
+
+    let mut conway = Universe::new();
+    let mut scripted = conway.clone();
+    for _ in 0..12 {
+        conway.tick();
+        scripted.tick_with_js_rule(&birth, &survival).unwrap();
+        assert_eq!(scripted.get_cells(), conway.get_cells());
+    }
+    assert_eq!(scripted.generation(), 12);
+}
+
+#[wasm_bindgen_test]
+fn js_rule_reports_a_throwing_function() {
+    let throws = js_sys::Function::new_with_args("n", "throw new Error('nope')");
+    let never = js_sys::Function::new_no_args("return false");
+
+    let mut universe = Universe::new();
+    let before = universe.get_cells().to_vec();
+    assert!(universe.tick_with_js_rule(&throws, &never).is_err());
+    assert_eq!(universe.get_cells(), &before[..]);
+}
+
+#[wasm_bindgen_test]
+fn cells_2d_nests_rows() {
+    let mut universe = Universe::empty(4, 3);
+    universe.set_cells(&[(0, 1), (2, 3)]);
+    let rows = universe.cells_2d();
+
+    assert_eq!(rows.length(), 3);
+    let first = js_sys::Array::from(&rows.get(0));
+    let last = js_sys::Array::from(&rows.get(2));
+    assert_eq!(first.length(), 4);
+    assert_eq!(first.get(0).as_f64(), Some(0.0));
+    assert_eq!(first.get(1).as_f64(), Some(1.0));
+    assert_eq!(last.get(3).as_f64(), Some(1.0));
+}
+
+#[wasm_bindgen_test]
+fn enumerate_still_lifes_returns_universes() {
+    let found = Universe::empty(6, 6)
+        .enumerate_still_lifes(4, 1000.0)
+        .unwrap();
+    assert_eq!(found.length(), 2);
+}